@@ -0,0 +1,208 @@
+//! Compares laying out a ~50k-node tree on one engine against splitting it across several
+//! engines, one per independent subtree, driven from a rayon pool.
+//!
+//! This measures a host-side strategy, not an engine code path: the engine has no parallel
+//! entry point and every engine below still computes serially. The tree is a root holding
+//! absolutely positioned, fixed-size panels, so no panel's layout depends on a sibling.
+//! Taffy computes a tree through `&mut TaffyTree` and offers no way to write layouts computed
+//! elsewhere back into it, so the split gives each panel its own engine and keeps only
+//! childless stand-ins for the panels under the root.
+//!
+//! Run with `cargo bench --bench multi_engine_subtrees`.
+
+use std::time::{Duration, Instant};
+
+use layout_engine::{
+    LayoutEngineState, StyleProp, compute_layout_from_buffers, create_engine, destroy_engine,
+    layout_engine_style_layout, layout_engine_style_prop_children_count,
+    layout_engine_style_prop_children_offset, layout_engine_style_prop_flex_direction,
+    layout_engine_style_prop_flex_shrink, layout_engine_style_prop_height,
+    layout_engine_style_prop_width, layout_engine_style_stride,
+};
+use rayon::prelude::*;
+
+const PANELS: usize = 64;
+const ROWS_PER_PANEL: usize = 30;
+const CELLS_PER_ROW: usize = 25;
+const RUNS: usize = 10;
+
+struct Buffers {
+    nodes: Vec<f32>,
+    children: Vec<u32>,
+}
+
+struct StyleBuilder {
+    stride: usize,
+    position_type: usize,
+}
+
+impl StyleBuilder {
+    fn new() -> Self {
+        let stride = layout_engine_style_stride() as usize;
+        let mut offsets = vec![0_u32; stride];
+        unsafe { layout_engine_style_layout(offsets.as_mut_ptr(), offsets.len()) };
+        Self {
+            stride,
+            position_type: offsets[StyleProp::PositionType as usize] as usize,
+        }
+    }
+
+    fn style(&self, width: f32, height: f32, direction: f32, absolute: bool) -> Vec<f32> {
+        let mut style = vec![0.0_f32; self.stride];
+        style[layout_engine_style_prop_flex_shrink() as usize] = 1.0;
+        style[StyleProp::FlexBasis as usize] = f32::NAN;
+        for prop in [
+            StyleProp::MinWidth,
+            StyleProp::MinHeight,
+            StyleProp::MaxWidth,
+            StyleProp::MaxHeight,
+        ] {
+            style[prop as usize] = f32::NAN;
+        }
+        style[layout_engine_style_prop_width() as usize] = width;
+        style[layout_engine_style_prop_height() as usize] = height;
+        style[layout_engine_style_prop_flex_direction() as usize] = direction;
+        if absolute {
+            style[self.position_type] = 1.0;
+        }
+        style
+    }
+}
+
+impl Buffers {
+    fn push(&mut self, mut style: Vec<f32>, children: &[u32]) {
+        style[layout_engine_style_prop_children_offset() as usize] = self.children.len() as f32;
+        style[layout_engine_style_prop_children_count() as usize] = children.len() as f32;
+        self.nodes.extend(style);
+        self.children.extend(children);
+    }
+
+    /// Appends a panel and its rows of cells, with ids starting at `first_id`.
+    fn push_panel(&mut self, styles: &StyleBuilder, first_id: u32) {
+        let rows: Vec<u32> = (0..ROWS_PER_PANEL as u32)
+            .map(|r| first_id + 1 + r)
+            .collect();
+        self.push(styles.style(100.0, 400.0, 1.0, true), &rows);
+
+        let first_cell = first_id + 1 + ROWS_PER_PANEL as u32;
+        for row in 0..ROWS_PER_PANEL as u32 {
+            let start = first_cell + row * CELLS_PER_ROW as u32;
+            let cells: Vec<u32> = (start..start + CELLS_PER_ROW as u32).collect();
+            self.push(styles.style(f32::NAN, 1.0, 0.0, false), &cells);
+        }
+        for _ in 0..ROWS_PER_PANEL * CELLS_PER_ROW {
+            self.push(styles.style(4.0, 1.0, 0.0, false), &[]);
+        }
+    }
+}
+
+fn panel_node_count() -> usize {
+    1 + ROWS_PER_PANEL + ROWS_PER_PANEL * CELLS_PER_ROW
+}
+
+fn full_tree(styles: &StyleBuilder) -> Buffers {
+    let mut buffers = Buffers {
+        nodes: Vec::new(),
+        children: Vec::new(),
+    };
+    let panels: Vec<u32> = (0..PANELS)
+        .map(|p| (1 + p * panel_node_count()) as u32)
+        .collect();
+    buffers.push(styles.style(800.0, 600.0, 1.0, false), &panels);
+    for panel in panels {
+        buffers.push_panel(styles, panel);
+    }
+    buffers
+}
+
+fn root_with_stand_ins(styles: &StyleBuilder) -> Buffers {
+    let mut buffers = Buffers {
+        nodes: Vec::new(),
+        children: Vec::new(),
+    };
+    let panels: Vec<u32> = (1..=PANELS as u32).collect();
+    buffers.push(styles.style(800.0, 600.0, 1.0, false), &panels);
+    for _ in 0..PANELS {
+        buffers.push(styles.style(100.0, 400.0, 1.0, true), &[]);
+    }
+    buffers
+}
+
+fn single_panel(styles: &StyleBuilder) -> Buffers {
+    let mut buffers = Buffers {
+        nodes: Vec::new(),
+        children: Vec::new(),
+    };
+    buffers.push_panel(styles, 0);
+    buffers
+}
+
+struct Engine(*mut LayoutEngineState);
+
+// Each engine is only ever touched by one thread at a time.
+unsafe impl Send for Engine {}
+
+impl Engine {
+    fn compute(&mut self, buffers: &Buffers) {
+        let status = unsafe {
+            compute_layout_from_buffers(
+                self.0,
+                buffers.nodes.as_ptr(),
+                buffers.nodes.len(),
+                buffers.children.as_ptr(),
+                buffers.children.len(),
+            )
+        };
+        assert_eq!(status, 0);
+    }
+}
+
+impl Drop for Engine {
+    fn drop(&mut self) {
+        unsafe { destroy_engine(self.0) };
+    }
+}
+
+fn median(mut samples: Vec<Duration>) -> Duration {
+    samples.sort();
+    samples[samples.len() / 2]
+}
+
+fn main() {
+    let styles = StyleBuilder::new();
+    let full = full_tree(&styles);
+    let root = root_with_stand_ins(&styles);
+    let panel = single_panel(&styles);
+    let node_count = full.nodes.len() / styles.stride;
+
+    let mut single_engine = Engine(create_engine());
+    single_engine.compute(&full);
+    let single = median(
+        (0..RUNS)
+            .map(|_| {
+                let start = Instant::now();
+                single_engine.compute(&full);
+                start.elapsed()
+            })
+            .collect(),
+    );
+
+    let mut root_engine = Engine(create_engine());
+    let mut panel_engines: Vec<Engine> = (0..PANELS).map(|_| Engine(create_engine())).collect();
+    let mut run_split = || {
+        let start = Instant::now();
+        root_engine.compute(&root);
+        panel_engines
+            .par_iter_mut()
+            .for_each(|engine| engine.compute(&panel));
+        start.elapsed()
+    };
+    run_split();
+    let split = median((0..RUNS).map(|_| run_split()).collect());
+
+    println!(
+        "{node_count} nodes: one engine {single:?}, {} engines on {} threads {split:?}",
+        PANELS + 1,
+        rayon::current_num_threads()
+    );
+}
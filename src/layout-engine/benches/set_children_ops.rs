@@ -0,0 +1,119 @@
+//! Counts heap allocations made by one `apply_ops_and_compute` call carrying a batch of
+//! SetChildren ops (the trailing layout pass is included in the count).
+//!
+//! Run with `cargo bench --bench set_children_ops`.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use layout_engine::{
+    StyleProp, apply_ops_and_compute, create_engine, destroy_engine,
+    layout_engine_opcode_create_leaf, layout_engine_opcode_set_children,
+    layout_engine_style_prop_flex_shrink, layout_engine_style_prop_height,
+    layout_engine_style_prop_width, layout_engine_style_stride,
+};
+
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+const PARENTS: u32 = 5000;
+const CHILDREN_PER_PARENT: u32 = 4;
+
+fn apply(
+    engine: *mut layout_engine::LayoutEngineState,
+    ops: &[u32],
+    styles: &[f32],
+    children: &[u32],
+) {
+    let status = unsafe {
+        apply_ops_and_compute(
+            engine,
+            ops.as_ptr(),
+            ops.len(),
+            styles.as_ptr(),
+            styles.len(),
+            children.as_ptr(),
+            children.len(),
+        )
+    };
+    assert_eq!(status, 0);
+}
+
+fn main() {
+    let stride = layout_engine_style_stride() as usize;
+    let mut style = vec![0.0_f32; stride];
+    style[layout_engine_style_prop_flex_shrink() as usize] = 1.0;
+    style[StyleProp::FlexBasis as usize] = f32::NAN;
+    for prop in [
+        StyleProp::MinWidth,
+        StyleProp::MinHeight,
+        StyleProp::MaxWidth,
+        StyleProp::MaxHeight,
+    ] {
+        style[prop as usize] = f32::NAN;
+    }
+    style[layout_engine_style_prop_width() as usize] = f32::NAN;
+    style[layout_engine_style_prop_height() as usize] = f32::NAN;
+
+    let node_count = 1 + PARENTS * (1 + CHILDREN_PER_PARENT);
+    let mut create_ops = Vec::new();
+    for id in 0..node_count {
+        create_ops.extend([layout_engine_opcode_create_leaf(), id, 0]);
+    }
+
+    // Parent ids are 1..=PARENTS; their children follow after them.
+    let mut children = Vec::new();
+    let mut wire_ops = vec![layout_engine_opcode_set_children(), 0, 0, PARENTS];
+    children.extend(1..=PARENTS);
+    for parent in 1..=PARENTS {
+        let offset = children.len() as u32;
+        let first_child = PARENTS + 1 + (parent - 1) * CHILDREN_PER_PARENT;
+        children.extend(first_child..first_child + CHILDREN_PER_PARENT);
+        wire_ops.extend([
+            layout_engine_opcode_set_children(),
+            parent,
+            offset,
+            CHILDREN_PER_PARENT,
+        ]);
+    }
+
+    let engine = create_engine();
+    apply(engine, &create_ops, &style, &[]);
+    // Warm up so buffers reach their steady-state capacity.
+    apply(engine, &wire_ops, &style, &children);
+
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    apply(engine, &wire_ops, &style, &children);
+    let elapsed = start.elapsed();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+
+    let op_count = PARENTS as usize + 1;
+    println!(
+        "{op_count} SetChildren ops: {allocations} allocations ({:.2}/op), {elapsed:?}",
+        allocations as f64 / op_count as f64
+    );
+
+    unsafe { destroy_engine(engine) };
+}
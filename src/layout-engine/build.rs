@@ -0,0 +1,33 @@
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+/// Set to rewrite the committed `include/layout_engine.h` instead of only checking it.
+const WRITE_HEADER_ENV: &str = "LAYOUT_ENGINE_WRITE_HEADER";
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR")?);
+    let out_dir = PathBuf::from(env::var("OUT_DIR")?);
+    let committed = crate_dir.join("include").join("layout_engine.h");
+
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    println!("cargo:rerun-if-changed=include/layout_engine.h");
+    println!("cargo:rerun-if-env-changed={WRITE_HEADER_ENV}");
+
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml"))?;
+    let generated = out_dir.join("layout_engine.h");
+    cbindgen::Builder::new()
+        .with_crate(&crate_dir)
+        .with_config(config)
+        .generate()?
+        .write_to_file(&generated);
+
+    if env::var_os(WRITE_HEADER_ENV).is_some() {
+        fs::copy(&generated, &committed)?;
+    } else if fs::read(&generated)? != fs::read(&committed).unwrap_or_default() {
+        println!("cargo:warning=include/layout_engine.h is out of date; run `mise run header:ffi`");
+    }
+    Ok(())
+}
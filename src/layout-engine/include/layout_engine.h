@@ -0,0 +1,471 @@
+#ifndef LAYOUT_ENGINE_H
+#define LAYOUT_ENGINE_H
+
+/* Generated by cbindgen from src/lib.rs. Do not edit by hand. */
+
+#include <stddef.h>
+#include <stdint.h>
+
+#define LAYOUT_ENGINE_ABI_VERSION 59
+
+/**
+ * Lane of a node's style slice; `layout_engine_style_layout` lists them in this order.
+ */
+enum StyleProp
+#if __STDC_VERSION__ >= 202311L
+  : uint32_t
+#endif // __STDC_VERSION__ >= 202311L
+ {
+  StyleProp_Display,
+  StyleProp_PositionType,
+  StyleProp_FlexDirection,
+  StyleProp_FlexWrap,
+  StyleProp_JustifyContent,
+  StyleProp_AlignItems,
+  StyleProp_AlignSelf,
+  StyleProp_FlexGrow,
+  StyleProp_FlexShrink,
+  StyleProp_FlexBasis,
+  StyleProp_Width,
+  StyleProp_Height,
+  StyleProp_MinWidth,
+  StyleProp_MinHeight,
+  StyleProp_MaxWidth,
+  StyleProp_MaxHeight,
+  StyleProp_MarginLeft,
+  StyleProp_MarginRight,
+  StyleProp_MarginTop,
+  StyleProp_MarginBottom,
+  StyleProp_PaddingLeft,
+  StyleProp_PaddingRight,
+  StyleProp_PaddingTop,
+  StyleProp_PaddingBottom,
+  StyleProp_GapRow,
+  StyleProp_GapColumn,
+  StyleProp_ChildrenCount,
+  StyleProp_ChildrenOffset,
+  StyleProp_OverflowX,
+  StyleProp_OverflowY,
+  StyleProp_ScrollbarWidth,
+  StyleProp_BoxSizing,
+  StyleProp_Direction,
+  StyleProp_AnchorRoot,
+  StyleProp_AlignContent,
+  StyleProp_JustifyItems,
+  StyleProp_JustifySelf,
+  StyleProp_GapRowUnit,
+  StyleProp_GapColumnUnit,
+  StyleProp_Order,
+  StyleProp_AspectRatio,
+  StyleProp_Visibility,
+  StyleProp_FlexBasisUnit,
+  StyleProp_TotalProps,
+};
+#if __STDC_VERSION__ >= 202311L
+typedef enum StyleProp StyleProp;
+#else
+typedef uint32_t StyleProp;
+#endif // __STDC_VERSION__ >= 202311L
+
+typedef struct LayoutEngineState LayoutEngineState;
+
+struct LayoutEngineState *create_engine(void);
+
+struct LayoutEngineState *create_engine_with_capacity(size_t node_hint);
+
+/**
+ * Returns an independent deep copy of the engine, tree, id maps and last results included,
+ * e.g. to try candidate layouts without disturbing the live engine. Returns null for a null
+ * engine; the copy is freed with `destroy_engine` like any other.
+ */
+struct LayoutEngineState *clone_engine(struct LayoutEngineState *engine_ptr);
+
+void destroy_engine(struct LayoutEngineState *ptr);
+
+void reset_engine(struct LayoutEngineState *ptr);
+
+void trim_engine_memory(struct LayoutEngineState *ptr);
+
+int32_t compute_layout_from_buffers(struct LayoutEngineState *engine_ptr,
+                                    const float *nodes_buffer_ptr,
+                                    size_t nodes_buffer_len,
+                                    const uint32_t *children_buffer_ptr,
+                                    size_t children_buffer_len);
+
+/**
+ * Like `compute_layout_from_buffers`, with an explicit js id per node in `ids_ptr` instead
+ * of array positions, so reordering the nodes array between frames keeps each node's id.
+ * Children buffer entries refer to these ids, and the tree is computed from id 0.
+ */
+int32_t compute_layout_from_buffers_with_ids(struct LayoutEngineState *engine_ptr,
+                                             const float *nodes_buffer_ptr,
+                                             size_t nodes_buffer_len,
+                                             const uint32_t *children_buffer_ptr,
+                                             size_t children_buffer_len,
+                                             const uint32_t *ids_ptr,
+                                             size_t ids_len);
+
+/**
+ * Like `compute_layout_from_buffers`, but first checks the style stride the caller encoded
+ * with against this library's and returns -37 on a mismatch, so a binding built against an
+ * older ABI fails loudly instead of having its buffers misread.
+ */
+int32_t compute_layout_from_buffers_checked(struct LayoutEngineState *engine_ptr,
+                                            const float *nodes_buffer_ptr,
+                                            size_t nodes_buffer_len,
+                                            const uint32_t *children_buffer_ptr,
+                                            size_t children_buffer_len,
+                                            uint32_t caller_stride);
+
+/**
+ * Like `compute_layout_from_buffers`, but for a forest: node 0 is not required, and every
+ * node without a parent is laid out as an independent root at the origin.
+ */
+int32_t compute_layout_forest_from_buffers(struct LayoutEngineState *engine_ptr,
+                                           const float *nodes_buffer_ptr,
+                                           size_t nodes_buffer_len,
+                                           const uint32_t *children_buffer_ptr,
+                                           size_t children_buffer_len);
+
+/**
+ * Same as `compute_layout_from_buffers`, then copies the results into caller memory and
+ * returns the number of floats written. Returns -31 if `out_cap` is too small; the required
+ * length is then available from `get_results_len`.
+ */
+int32_t compute_layout_into(struct LayoutEngineState *engine_ptr,
+                            const float *nodes_buffer_ptr,
+                            size_t nodes_buffer_len,
+                            const uint32_t *children_buffer_ptr,
+                            size_t children_buffer_len,
+                            float *out_ptr,
+                            size_t out_cap);
+
+/**
+ * Same as `compute_layout_from_buffers`, then writes the results pointer and length (in
+ * floats) to `out_ptr_out` and `out_len_out`, saving hosts with costly calls the separate
+ * `get_results_ptr`/`get_results_len` crossings. The pointer stays valid until the next call
+ * that changes the engine; the out-params are left untouched on failure.
+ */
+int32_t compute_layout_and_emit(struct LayoutEngineState *engine_ptr,
+                                const float *nodes_buffer_ptr,
+                                size_t nodes_buffer_len,
+                                const uint32_t *children_buffer_ptr,
+                                size_t children_buffer_len,
+                                const float **out_ptr_out,
+                                size_t *out_len_out);
+
+/**
+ * Builds the tree from a UTF-8 JSON description shaped like the TS `LayoutInputNode`
+ * and computes it. Js ids are assigned in depth-first pre-order, starting at 0 for the root.
+ */
+int32_t compute_layout_from_json(struct LayoutEngineState *engine_ptr,
+                                 const uint8_t *json_ptr,
+                                 size_t json_len);
+
+/**
+ * Applies a batch of incremental ops in order, then computes from node 0.
+ *
+ * The batch is all-or-nothing: it is validated before anything is applied, and a rejected
+ * batch leaves the tree and the results buffer as they were. An id removed earlier in the
+ * batch is unknown to later ops unless a `CreateLeaf` recreates it, and a `CreateLeaf` for
+ * an id that is still live is rejected with -51. `RemoveNode` leaves the node's children in
+ * place without a parent; `RemoveSubtree` removes them too.
+ *
+ * `SetStylePartial` (`[op, node, offset, count]`) patches only some style lanes of a node:
+ * `styles[offset..offset + 2 * count]` holds `(lane index, value)` pairs, and every other
+ * lane keeps the value the node last had.
+ */
+int32_t apply_ops_and_compute(struct LayoutEngineState *engine_ptr,
+                              const uint32_t *ops_ptr,
+                              size_t ops_len,
+                              const float *styles_ptr,
+                              size_t styles_len,
+                              const uint32_t *children_ptr,
+                              size_t children_len);
+
+/**
+ * Like `apply_ops_and_compute`, without the compute: the batch is applied all-or-nothing
+ * and the results buffer keeps the last computed layout. Lets a host build a tree across
+ * several batches, including before node 0 exists, and lay it out once with
+ * `compute_current`.
+ */
+int32_t apply_ops(struct LayoutEngineState *engine_ptr,
+                  const uint32_t *ops_ptr,
+                  size_t ops_len,
+                  const float *styles_ptr,
+                  size_t styles_len,
+                  const uint32_t *children_ptr,
+                  size_t children_len);
+
+/**
+ * Lays out the current tree from `root_js_id` under the given available size (NaN for an
+ * unconstrained axis) and emits every node, as after a full compute. Pairs with
+ * `apply_ops` to compute once after several batches.
+ */
+int32_t compute_current(struct LayoutEngineState *engine_ptr,
+                        uint32_t root_js_id,
+                        float available_width,
+                        float available_height);
+
+int32_t compute_layout_for_root(struct LayoutEngineState *engine_ptr,
+                                uint32_t root_js_id,
+                                float available_width,
+                                float available_height);
+
+/**
+ * Recomputes the current tree from node 0 under a new available size (NaN for an
+ * unconstrained axis) without rebuilding it, e.g. when only the viewport was resized.
+ * Results cover every node, as after a full compute.
+ */
+int32_t recompute_with_available(struct LayoutEngineState *engine_ptr,
+                                 float available_width,
+                                 float available_height);
+
+/**
+ * Like `compute_layout_for_root`, with an explicit available-space mode per axis:
+ * 0 for max-content, 1 for min-content, 2 for a definite size given by the matching value.
+ */
+int32_t compute_layout_for_root_with_modes(struct LayoutEngineState *engine_ptr,
+                                           uint32_t root_js_id,
+                                           uint32_t width_mode,
+                                           float width,
+                                           uint32_t height_mode,
+                                           float height);
+
+/**
+ * Measures the intrinsic size of the subtree rooted at `root_js_id` and writes
+ * `[width, height]` to `out_ptr`. Each axis mode is 0 for max-content or 1 for min-content.
+ * The results buffer and the layouts from the last compute are left untouched.
+ */
+int32_t measure_intrinsic(struct LayoutEngineState *engine_ptr,
+                          uint32_t root_js_id,
+                          uint32_t width_mode,
+                          uint32_t height_mode,
+                          float *out_ptr);
+
+/**
+ * Lays out the subtree rooted at `js_id` under the given available size (NaN for an
+ * unconstrained axis) and writes its `[width, height]` to `out_ptr`, e.g. to find how tall
+ * a label gets at a given max width. Like `measure_intrinsic`, the results buffer and the
+ * layouts from the last compute are left untouched.
+ */
+int32_t measure_node(struct LayoutEngineState *engine_ptr,
+                     uint32_t js_id,
+                     float available_width,
+                     float available_height,
+                     float *out_ptr);
+
+/**
+ * Writes a UTF-8 outline of the subtree rooted at `root_js_id` (from the last compute) into
+ * `out_ptr`, truncated to `out_cap` bytes, and returns the full length in bytes. Returns 0
+ * for a null engine or an unknown root.
+ */
+size_t dump_tree(struct LayoutEngineState *engine_ptr,
+                 uint32_t root_js_id,
+                 uint8_t *out_ptr,
+                 size_t out_cap);
+
+/**
+ * Writes a JSON snapshot of the input tree (styles and children by js id) into `out_ptr`,
+ * truncated to `out_cap` bytes, and returns the full length in bytes, or 0 for a null engine.
+ */
+size_t export_snapshot(struct LayoutEngineState *engine_ptr, uint8_t *out_ptr, size_t out_cap);
+
+/**
+ * Replaces the tree with one read from an `export_snapshot` JSON and computes it from
+ * node 0. On error the current tree is left as it was.
+ */
+int32_t import_snapshot(struct LayoutEngineState *engine_ptr,
+                        const uint8_t *json_ptr,
+                        size_t json_len);
+
+/**
+ * Writes the js ids whose emitted rect changed in the last compute (including newly added
+ * nodes, then nodes that were emitted before but no longer are) into `out_ptr`, up to
+ * `out_cap` ids, and returns how many there are in total.
+ */
+size_t get_changed_node_ids(struct LayoutEngineState *engine_ptr,
+                            uint32_t *out_ptr,
+                            size_t out_cap);
+
+/**
+ * Turns per-compute phase timings on (nonzero) or off (0). Timings are off by default and
+ * cost nothing while off.
+ */
+void set_timings_enabled(struct LayoutEngineState *engine_ptr, uint32_t enabled);
+
+/**
+ * Leaves `display: none` nodes and all their descendants out of the results from the next
+ * compute on (nonzero), or emits every node (0, the default).
+ */
+void set_skip_hidden_results(struct LayoutEngineState *engine_ptr, uint32_t skip);
+
+/**
+ * Reports in-flow nodes with a zero main-axis size from the next compute on (nonzero) at
+ * the position the flow would give the next item, past their last sized sibling, its margin
+ * and the gap, instead of wherever alignment left them (0, the default). Only flex and block
+ * parents are affected.
+ */
+void set_zero_size_flow_positions(struct LayoutEngineState *engine_ptr, uint32_t enabled);
+
+/**
+ * Writes the timings of the last compute to `out_ptr` as four floats: the number of emitted
+ * nodes, then the milliseconds spent building the tree, computing layout and emitting
+ * results. All zeros if nothing was computed while timings were enabled.
+ */
+int32_t get_last_timings(struct LayoutEngineState *engine_ptr, float *out_ptr);
+
+/**
+ * Writes `[hits, misses, node_count]` for the last compute to `out_ptr`. A hit is a node
+ * whose cached layout was still valid going in; a miss is one that was dirty.
+ */
+int32_t get_cache_stats(struct LayoutEngineState *engine_ptr, uint32_t *out_ptr);
+
+/**
+ * After a call failed with -28, writes `[js_id, style_lane]` of the infinite value that
+ * was rejected to `out_ptr`. Returns -30 if the last call rejected nothing.
+ */
+int32_t get_invalid_style(struct LayoutEngineState *engine_ptr, uint32_t *out_ptr);
+
+/**
+ * Writes the unrounded `[x, y, width, height]` of `node_js_id` from the last compute
+ * into `out_ptr`. The results buffer carries the rounded rects of the same pass.
+ */
+int32_t get_node_layout_unrounded(struct LayoutEngineState *engine_ptr,
+                                  uint32_t node_js_id,
+                                  float *out_ptr);
+
+/**
+ * Writes the first baseline of `node_js_id` from the last compute to `out_ptr`, as an offset
+ * from the node's top edge. A node without in-flow children reports its bottom edge.
+ */
+int32_t get_node_baseline(struct LayoutEngineState *engine_ptr,
+                          uint32_t node_js_id,
+                          float *out_ptr);
+
+/**
+ * Writes `[x, y, width, height]` of `node_js_id` from the last compute to `out_ptr`, with the
+ * location in root coordinates instead of relative to the node's parent.
+ */
+int32_t get_absolute_layout(struct LayoutEngineState *engine_ptr,
+                            uint32_t node_js_id,
+                            float *out_ptr);
+
+/**
+ * Finds the topmost, deepest node of `root_js_id`'s subtree drawn at `(x, y)` in root
+ * coordinates, as of the last compute. Returns 1 and writes its js id to `out_ptr` on a hit,
+ * 0 if no node is there, or a negative status.
+ */
+int32_t hit_test(struct LayoutEngineState *engine_ptr,
+                 uint32_t root_js_id,
+                 float x,
+                 float y,
+                 uint32_t *out_ptr);
+
+/**
+ * Writes the `[x, y, width, height]` union of the rects of the `ids_len` nodes at `ids_ptr`
+ * from the last compute to `out_ptr`, in root coordinates. An empty set writes an empty box
+ * at the origin.
+ */
+int32_t get_bounding_box(struct LayoutEngineState *engine_ptr,
+                         const uint32_t *ids_ptr,
+                         size_t ids_len,
+                         float *out_ptr);
+
+/**
+ * Returns how many flex lines the children of `node_js_id` wrapped into in the last compute,
+ * or a negative status. A container that does not wrap always reports 1.
+ */
+int32_t get_flex_line_count(struct LayoutEngineState *engine_ptr, uint32_t node_js_id);
+
+/**
+ * Returns 1 if `node_js_id` should be drawn and 0 if its `visibility` resolves to hidden,
+ * or a negative status. Hidden nodes keep their box in the results; only drawing is skipped.
+ */
+int32_t get_node_visibility(struct LayoutEngineState *engine_ptr, uint32_t node_js_id);
+
+/**
+ * Returns 1 if a node with `node_js_id` currently exists and 0 if not, so a reconciler can
+ * choose between `CreateLeaf` and `UpdateStyle` without probing with an op batch.
+ */
+int32_t layout_engine_has_node(struct LayoutEngineState *engine_ptr, uint32_t node_js_id);
+
+const float *get_results_ptr(struct LayoutEngineState *engine_ptr);
+
+size_t get_results_len(struct LayoutEngineState *engine_ptr);
+
+/**
+ * Copies the results of up to `max_nodes` nodes, starting at the `start_node`-th emitted
+ * node, into `out_ptr` and returns how many nodes were written. Fewer are written when
+ * `out_cap` (in floats) or the end of the results comes first, so a host can page through
+ * a large results buffer in fixed-size reads.
+ */
+size_t get_results_chunk(struct LayoutEngineState *engine_ptr,
+                         size_t start_node,
+                         size_t max_nodes,
+                         float *out_ptr,
+                         size_t out_cap);
+
+size_t get_results_capacity(struct LayoutEngineState *engine_ptr);
+
+/**
+ * Returns a static, NUL-terminated message describing a status code returned by this
+ * library. Codes in -100..=-199 are failures reported by Taffy. The pointer stays valid for
+ * the lifetime of the process and must not be freed.
+ */
+const char *layout_engine_error_string(int32_t code);
+
+uint32_t layout_engine_abi_version(void);
+
+uint32_t layout_engine_style_stride(void);
+
+uint32_t layout_engine_result_stride(void);
+
+uint32_t layout_engine_f32_size(void);
+
+uint32_t layout_engine_u32_size(void);
+
+uint32_t layout_engine_style_prop_flex_grow(void);
+
+uint32_t layout_engine_style_prop_flex_shrink(void);
+
+uint32_t layout_engine_style_prop_flex_direction(void);
+
+uint32_t layout_engine_style_prop_width(void);
+
+uint32_t layout_engine_style_prop_height(void);
+
+uint32_t layout_engine_style_prop_gap_row(void);
+
+uint32_t layout_engine_style_prop_gap_column(void);
+
+uint32_t layout_engine_style_prop_children_count(void);
+
+uint32_t layout_engine_style_prop_children_offset(void);
+
+uint32_t layout_engine_opcode_create_leaf(void);
+
+uint32_t layout_engine_opcode_update_style(void);
+
+uint32_t layout_engine_opcode_set_children(void);
+
+uint32_t layout_engine_opcode_remove_node(void);
+
+uint32_t layout_engine_opcode_remove_subtree(void);
+
+uint32_t layout_engine_opcode_set_style_partial(void);
+
+/**
+ * Writes up to `out_len` entries of the ABI info, in this fixed order: ABI version, style
+ * stride, result stride, f32 size, u32 size. Returns the full entry count; entries are
+ * only ever appended, so the version tells bindings which ones to expect.
+ */
+size_t layout_engine_abi_info(uint32_t *out_ptr, size_t out_len);
+
+/**
+ * Writes up to `out_len` entries of the style offset table (one per `StyleProp`, in
+ * declaration order) and returns the full table length.
+ */
+size_t layout_engine_style_layout(uint32_t *out_ptr, size_t out_len);
+
+#endif  /* LAYOUT_ENGINE_H */
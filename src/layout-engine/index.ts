@@ -1,6 +1,6 @@
 import { dlopen, FFIType, suffix, ptr, toArrayBuffer } from "bun:ffi";
 import path from "node:path";
-import type { LayoutInputNode, ComputedLayout, Dimension, LayoutStyle } from "./types";
+import type { LayoutInputNode, ComputedLayout, Dimension, GapValue, LayoutStyle } from "./types";
 import { existsSync } from "node:fs";
 
 // --- Data Layout Constants (must match Rust) ---
@@ -14,6 +14,11 @@ enum StyleProp {
   PaddingLeft, PaddingRight, PaddingTop, PaddingBottom,
   GapRow, GapColumn,
   ChildrenCount, ChildrenOffset,
+  OverflowX, OverflowY, ScrollbarWidth,
+  BoxSizing, Direction, AnchorRoot,
+  AlignContent, JustifyItems, JustifySelf,
+  GapRowUnit, GapColumnUnit, Order, AspectRatio, Visibility,
+  FlexBasisUnit,
   TotalProps,
 }
 const STYLE_STRIDE = StyleProp.TotalProps;
@@ -24,6 +29,8 @@ enum LayoutOp {
   UpdateStyle = 2,
   SetChildren = 3,
   RemoveNode = 4,
+  RemoveSubtree = 5,
+  SetStylePartial = 6,
 }
 
 // --- Helper Functions for Serialization ---
@@ -33,17 +40,33 @@ function dimToFloat(dim: Dimension | undefined): number {
   return NaN; // Represents 'auto'
 }
 
-function gapToPair(gap: LayoutStyle["gap"] | undefined): [number, number] {
-  if (typeof gap === "number") return [gap, gap];
-  if (gap && typeof gap === "object") return [gap.height ?? 0, gap.width ?? 0];
+/** Returns the (value, unit) lanes for a flex basis: NaN is auto, unit 1 a percentage. */
+function basisToLanes(basis: Dimension | undefined): [number, number] {
+  if (typeof basis === "number") return [basis, 0];
+  if (typeof basis === "string" && basis.endsWith("%")) return [parseFloat(basis), 1];
+  return [NaN, 0];
+}
+
+/** Returns the (value, unit) lanes for one gap axis; unit 1 marks a percentage. */
+function gapToLanes(gap: GapValue | undefined): [number, number] {
+  if (typeof gap === "number") return [gap, 0];
+  if (typeof gap === "string") return [parseFloat(gap) || 0, 1];
   return [0, 0];
 }
 
+function gapToPair(gap: LayoutStyle["gap"] | undefined): [[number, number], [number, number]] {
+  if (gap && typeof gap === "object") return [gapToLanes(gap.height), gapToLanes(gap.width)];
+  return [gapToLanes(gap), gapToLanes(gap)];
+}
+
 function boxToQuad(
   value: LayoutStyle["margin"] | LayoutStyle["padding"] | undefined,
 ): [number, number, number, number] {
   if (typeof value === "number") return [value, value, value, value];
-  if (Array.isArray(value) && value.length === 4) return value as [number, number, number, number];
+  if (value === "auto") return [NaN, NaN, NaN, NaN];
+  if (Array.isArray(value) && value.length === 4) {
+    return value.map(dimToFloat) as [number, number, number, number];
+  }
   return [0, 0, 0, 0];
 }
 
@@ -51,8 +74,18 @@ function writeStyle(out: Float32Array, node: LayoutInputNode) {
   out.fill(0);
   const style: LayoutStyle = node;
 
+  const displayMap: Record<string, number> = {
+    flex: 0,
+    none: 1,
+    block: 2,
+    grid: 3,
+    contents: 4,
+  };
+  out[StyleProp.Display] = displayMap[style.display ?? "flex"] ?? 0;
+
   out[StyleProp.FlexGrow] = style.flexGrow ?? 0;
   out[StyleProp.FlexShrink] = style.flexShrink ?? 1;
+  [out[StyleProp.FlexBasis], out[StyleProp.FlexBasisUnit]] = basisToLanes(style.flexBasis);
 
   const flexDirectionMap: Record<string, number> = {
     row: 0,
@@ -62,9 +95,16 @@ function writeStyle(out: Float32Array, node: LayoutInputNode) {
   };
   out[StyleProp.FlexDirection] = flexDirectionMap[style.flexDirection ?? "row"] ?? 0;
 
+  const flexWrapMap: Record<string, number> = {
+    nowrap: 0,
+    wrap: 1,
+    "wrap-reverse": 2,
+  };
+  out[StyleProp.FlexWrap] = flexWrapMap[style.flexWrap ?? "nowrap"] ?? 0;
+
   const [gapRow, gapColumn] = gapToPair(style.gap);
-  out[StyleProp.GapRow] = gapRow;
-  out[StyleProp.GapColumn] = gapColumn;
+  [out[StyleProp.GapRow], out[StyleProp.GapRowUnit]] = gapRow;
+  [out[StyleProp.GapColumn], out[StyleProp.GapColumnUnit]] = gapColumn;
 
   const justifyContentMap: Record<string, number> = {
     "flex-start": 0,
@@ -85,6 +125,38 @@ function writeStyle(out: Float32Array, node: LayoutInputNode) {
   };
   out[StyleProp.AlignItems] = alignItemsMap[style.alignItems ?? "stretch"] ?? 4;
 
+  const alignContentMap: Record<string, number> = {
+    stretch: 0,
+    "flex-start": 1,
+    "flex-end": 2,
+    center: 3,
+    "space-between": 4,
+    "space-around": 5,
+    "space-evenly": 6,
+  };
+  out[StyleProp.AlignContent] = alignContentMap[style.alignContent ?? "stretch"] ?? 0;
+
+  const alignSelfMap: Record<string, number> = {
+    auto: 0,
+    "flex-start": 1,
+    "flex-end": 2,
+    center: 3,
+    baseline: 4,
+    stretch: 5,
+  };
+  out[StyleProp.AlignSelf] = alignSelfMap[style.alignSelf ?? "auto"] ?? 0;
+
+  const justifyMap: Record<string, number> = {
+    normal: 0,
+    auto: 0,
+    start: 1,
+    end: 2,
+    center: 3,
+    stretch: 4,
+  };
+  out[StyleProp.JustifyItems] = justifyMap[style.justifyItems ?? "normal"] ?? 0;
+  out[StyleProp.JustifySelf] = justifyMap[style.justifySelf ?? "auto"] ?? 0;
+
   const positionTypeMap: Record<string, number> = {
     relative: 0,
     absolute: 1,
@@ -93,12 +165,44 @@ function writeStyle(out: Float32Array, node: LayoutInputNode) {
 
   out[StyleProp.Width] = dimToFloat(style.width);
   out[StyleProp.Height] = dimToFloat(style.height);
+  out[StyleProp.MinWidth] = dimToFloat(style.minWidth);
+  out[StyleProp.MinHeight] = dimToFloat(style.minHeight);
+  out[StyleProp.MaxWidth] = dimToFloat(style.maxWidth);
+  out[StyleProp.MaxHeight] = dimToFloat(style.maxHeight);
+  out[StyleProp.AspectRatio] = style.aspectRatio ?? 0;
 
   const marginArr = boxToQuad(style.margin);
   out.set(marginArr, StyleProp.MarginLeft);
 
   const paddingArr = boxToQuad(style.padding);
   out.set(paddingArr, StyleProp.PaddingLeft);
+
+  const overflowMap: Record<string, number> = {
+    visible: 0,
+    clip: 1,
+    hidden: 2,
+    scroll: 3,
+  };
+  out[StyleProp.OverflowX] = overflowMap[style.overflowX ?? style.overflow ?? "visible"] ?? 0;
+  out[StyleProp.OverflowY] = overflowMap[style.overflowY ?? style.overflow ?? "visible"] ?? 0;
+  out[StyleProp.ScrollbarWidth] = style.scrollbarWidth ?? 0;
+
+  const boxSizingMap: Record<string, number> = {
+    "border-box": 0,
+    "content-box": 1,
+  };
+  out[StyleProp.BoxSizing] = boxSizingMap[style.boxSizing ?? "border-box"] ?? 0;
+
+  out[StyleProp.Direction] = style.direction === "rtl" ? 1 : 0;
+  out[StyleProp.AnchorRoot] = style.anchorRoot ? 1 : 0;
+  out[StyleProp.Order] = style.order ?? 0;
+
+  const visibilityMap: Record<string, number> = {
+    inherit: 0,
+    hidden: 1,
+    visible: 2,
+  };
+  out[StyleProp.Visibility] = visibilityMap[style.visibility ?? "inherit"] ?? 0;
 }
 
 function sameFloat(a: number, b: number): boolean {
@@ -140,60 +244,8 @@ function serializeTree(root: LayoutInputNode): {
   for (let i = 0; i < nodeCount; i++) {
     const node = flatNodes[i];
     if (!node) continue;
-    const style: LayoutStyle = node;
     const offset = i * STYLE_STRIDE;
-
-    nodesBuffer[offset + StyleProp.FlexGrow] = style.flexGrow ?? 0;
-    nodesBuffer[offset + StyleProp.FlexShrink] = style.flexShrink ?? 1;
-
-    const flexDirectionMap: Record<string, number> = {
-      row: 0,
-      column: 1,
-      "row-reverse": 2,
-      "column-reverse": 3,
-    };
-    nodesBuffer[offset + StyleProp.FlexDirection] =
-      flexDirectionMap[style.flexDirection ?? "row"] ?? 0;
-
-    const [gapRow, gapColumn] = gapToPair(style.gap);
-    nodesBuffer[offset + StyleProp.GapRow] = gapRow;
-    nodesBuffer[offset + StyleProp.GapColumn] = gapColumn;
-
-    const justifyContentMap: Record<string, number> = {
-      "flex-start": 0,
-      "flex-end": 1,
-      center: 2,
-      "space-between": 3,
-      "space-around": 4,
-      "space-evenly": 5,
-    };
-    nodesBuffer[offset + StyleProp.JustifyContent] =
-      justifyContentMap[style.justifyContent ?? "flex-start"] ?? 0;
-
-    const alignItemsMap: Record<string, number> = {
-      "flex-start": 0,
-      "flex-end": 1,
-      center: 2,
-      baseline: 3,
-      stretch: 4,
-    };
-    nodesBuffer[offset + StyleProp.AlignItems] = alignItemsMap[style.alignItems ?? "stretch"] ?? 4;
-
-    const positionTypeMap: Record<string, number> = {
-      relative: 0,
-      absolute: 1,
-    };
-    nodesBuffer[offset + StyleProp.PositionType] =
-      positionTypeMap[style.position ?? "relative"] ?? 0;
-
-    nodesBuffer[offset + StyleProp.Width] = dimToFloat(style.width);
-    nodesBuffer[offset + StyleProp.Height] = dimToFloat(style.height);
-
-    const marginArr = boxToQuad(style.margin);
-    nodesBuffer.set(marginArr, offset + StyleProp.MarginLeft);
-
-    const paddingArr = boxToQuad(style.padding);
-    nodesBuffer.set(paddingArr, offset + StyleProp.PaddingLeft);
+    writeStyle(nodesBuffer.subarray(offset, offset + STYLE_STRIDE), node);
 
     const children = node.children ?? [];
     nodesBuffer[offset + StyleProp.ChildrenOffset] = childrenBufferData.length;
@@ -253,6 +305,7 @@ const { symbols } = dlopen(libPath(), {
   },
   get_results_ptr: { args: [FFIType.ptr], returns: FFIType.ptr },
   get_results_len: { args: [FFIType.ptr], returns: FFIType.u64 },
+  layout_engine_error_string: { args: [FFIType.i32], returns: FFIType.cstring },
 });
 
 // --- Memory Management ---
@@ -314,7 +367,8 @@ class LayoutEngineJS {
     );
 
     if (status !== 0) {
-      throw new Error(`Layout computation failed with status: ${status}`);
+      const message = symbols.layout_engine_error_string(status);
+      throw new Error(`Layout computation failed with status: ${status} (${message})`);
     }
 
     const computedLayout: ComputedLayout = {};
@@ -0,0 +1,250 @@
+//! Root anchoring for absolutely positioned nodes (`position: fixed`-like overlays).
+//!
+//! Taffy places an absolute node against its parent, so a node that should be placed against
+//! the layout root is wired under node 0 instead while the engine remembers its logical parent
+//! and slot. Emitted locations are converted back to be relative to the logical parent, so
+//! hosts keep accumulating offsets down the tree they built.
+
+use taffy::prelude::*;
+use taffy::{Point, TaffyResult};
+
+use super::LayoutEngineState;
+
+impl LayoutEngineState {
+    /// Sets `parent`'s children from `children_scratch`, diverting children that are anchored
+    /// to the root. Wiring node 0 keeps every anchored node at the end of its children.
+    pub(crate) fn wire_children(&mut self, parent: NodeId) -> TaffyResult<()> {
+        self.record_host_slots();
+        let mut changed = false;
+        for i in 0..self.children_scratch.len() {
+            let child = self.children_scratch[i];
+            changed |= self.anchored.remove(&child).is_some();
+            self.detach_from_contents(child);
+        }
+        let before = self.anchored.len();
+        self.anchored
+            .retain(|_, (logical_parent, _)| *logical_parent != parent);
+        changed |= self.anchored.len() != before;
+
+        if self.nodes.get(&0) == Some(&parent) {
+            self.sort_scratch_by_order();
+            self.root_flow_children.clear();
+            self.root_flow_children
+                .extend_from_slice(&self.children_scratch);
+            self.expand_contents_in_scratch();
+            self.append_anchored_to_scratch();
+            return self.taffy.set_children(parent, &self.children_scratch);
+        }
+
+        if !self.anchor_flags.is_empty() {
+            let anchored = &mut self.anchored;
+            let flags = &self.anchor_flags;
+            let mut index = 0;
+            self.children_scratch.retain(|child| {
+                let keep = !flags.contains(child);
+                if !keep {
+                    anchored.insert(*child, (parent, index));
+                    changed = true;
+                }
+                index += 1;
+                keep
+            });
+        }
+        self.sort_scratch_by_order();
+        if self.is_contents(parent) {
+            self.wire_contents_children(parent)?;
+        } else {
+            self.expand_contents_in_scratch();
+            self.taffy.set_children(parent, &self.children_scratch)?;
+        }
+        if changed {
+            self.sync_root_children()?;
+        }
+        Ok(())
+    }
+
+    /// Records a style's anchor flag for a node that is not wired yet.
+    pub(crate) fn set_anchor_flag(&mut self, node: NodeId, anchored: bool) {
+        if anchored {
+            self.anchor_flags.insert(node);
+        } else {
+            self.anchor_flags.remove(&node);
+        }
+    }
+
+    /// Applies an anchor flag change from a style update, moving an already wired node
+    /// between its logical parent and the root.
+    pub(crate) fn update_anchor_flag(&mut self, node: NodeId, anchored: bool) -> TaffyResult<()> {
+        if self.anchor_flags.contains(&node) == anchored {
+            return Ok(());
+        }
+        self.set_anchor_flag(node, anchored);
+        let root = self.nodes.get(&0).copied();
+
+        if anchored {
+            let Some(parent) = self.taffy.parent(node) else {
+                return Ok(());
+            };
+            if Some(parent) == root {
+                return Ok(());
+            }
+            let flow_index = self
+                .taffy
+                .child_ids(parent)
+                .position(|child| child == node)
+                .unwrap_or(0);
+            let mut sibling_slots: Vec<usize> = self
+                .anchored
+                .values()
+                .filter(|(logical_parent, _)| *logical_parent == parent)
+                .map(|(_, slot)| *slot)
+                .collect();
+            sibling_slots.sort_unstable();
+            let mut slot = flow_index;
+            for sibling_slot in sibling_slots {
+                if sibling_slot <= slot {
+                    slot += 1;
+                }
+            }
+            self.taffy.remove_child(parent, node)?;
+            self.anchored.insert(node, (parent, slot));
+        } else {
+            let Some((parent, slot)) = self.anchored.remove(&node) else {
+                return Ok(());
+            };
+            if let Some(root) = root
+                && self.taffy.parent(node) == Some(root)
+            {
+                self.taffy.remove_child(root, node)?;
+            }
+            let earlier = self
+                .anchored
+                .values()
+                .filter(|(logical_parent, other)| *logical_parent == parent && *other < slot)
+                .count();
+            let index = (slot - earlier).min(self.taffy.child_count(parent));
+            self.taffy.insert_child_at_index(parent, index, node)?;
+        }
+        self.sync_root_children()
+    }
+
+    /// Drops anchoring state that refers to `removed`, before it leaves the Taffy tree.
+    /// Returns true if the root's children must be re-synced afterwards.
+    pub(crate) fn forget_anchoring(&mut self, removed: NodeId) -> bool {
+        self.anchor_flags.remove(&removed);
+        self.root_flow_children.retain(|child| *child != removed);
+        if self.nodes.get(&0) == Some(&removed) {
+            self.root_flow_children.clear();
+        }
+
+        let changed = self.anchored.remove(&removed).is_some();
+        let before = self.anchored.len();
+        self.anchored
+            .retain(|_, (logical_parent, _)| *logical_parent != removed);
+        changed | (self.anchored.len() != before)
+    }
+
+    /// Rewires node 0 with its own children followed by every anchored node.
+    pub(crate) fn sync_root_children(&mut self) -> TaffyResult<()> {
+        let Some(root) = self.nodes.get(&0).copied() else {
+            return Ok(());
+        };
+        let taffy = &self.taffy;
+        let anchored = &self.anchored;
+        self.children_scratch.clear();
+        self.children_scratch.extend(
+            self.root_flow_children.iter().copied().filter(|child| {
+                taffy.parent(*child) == Some(root) && !anchored.contains_key(child)
+            }),
+        );
+        self.expand_contents_in_scratch();
+        self.append_anchored_to_scratch();
+        self.taffy.set_children(root, &self.children_scratch)
+    }
+
+    fn append_anchored_to_scratch(&mut self) {
+        let start = self.children_scratch.len();
+        self.children_scratch.extend(self.anchored.keys().copied());
+        let ids = &self.node_id_map;
+        self.children_scratch[start..].sort_unstable_by_key(|node| ids.get(node).copied());
+    }
+
+    /// Returns `parent`'s children as the host wired them, with anchored nodes back in
+    /// their slots.
+    pub(crate) fn logical_children(&self, parent: NodeId) -> Vec<NodeId> {
+        if self.nodes.get(&0) == Some(&parent) {
+            return self.root_flow_children.clone();
+        }
+        let mut children: Vec<NodeId> = match self.contents_children.get(&parent) {
+            Some(children) => children.clone(),
+            None => self
+                .taffy
+                .child_ids(parent)
+                .filter(|child| !self.contents_parent.contains_key(child))
+                .collect(),
+        };
+        let mut slots: Vec<(usize, NodeId)> = self
+            .anchored
+            .iter()
+            .filter(|(_, (logical_parent, _))| *logical_parent == parent)
+            .map(|(node, (_, slot))| (*slot, *node))
+            .collect();
+        slots.sort_unstable_by_key(|(slot, _)| *slot);
+        for (slot, node) in slots {
+            children.insert(slot.min(children.len()), node);
+        }
+        children
+    }
+
+    /// Converts a computed location to be relative to the node's logical parent.
+    pub(crate) fn logical_location(&self, node: NodeId, location: Point<f32>) -> Point<f32> {
+        self.logical_location_from(node, location, false)
+    }
+
+    /// Like `logical_location`, for a location read from Taffy's unrounded layout.
+    pub(crate) fn logical_location_unrounded(
+        &self,
+        node: NodeId,
+        location: Point<f32>,
+    ) -> Point<f32> {
+        self.logical_location_from(node, location, true)
+    }
+
+    fn logical_location_from(
+        &self,
+        node: NodeId,
+        location: Point<f32>,
+        unrounded: bool,
+    ) -> Point<f32> {
+        let Some((parent, _)) = self.anchored.get(&node) else {
+            return location;
+        };
+        let root_offset = self
+            .taffy
+            .parent(node)
+            .map_or(Point::ZERO, |root| self.absolute_location(root, unrounded));
+        let parent_offset = self.absolute_location(*parent, unrounded);
+        Point {
+            x: location.x + root_offset.x - parent_offset.x,
+            y: location.y + root_offset.y - parent_offset.y,
+        }
+    }
+
+    pub(crate) fn absolute_location(&self, node: NodeId, unrounded: bool) -> Point<f32> {
+        let mut offset = Point::ZERO;
+        let mut current = Some(node);
+        while let Some(node) = current {
+            let location = if unrounded {
+                Some(self.taffy.unrounded_layout(node).location)
+            } else {
+                self.taffy.layout(node).ok().map(|layout| layout.location)
+            };
+            if let Some(location) = location {
+                offset.x += location.x;
+                offset.y += location.y;
+            }
+            current = self.taffy.parent(node);
+        }
+        offset
+    }
+}
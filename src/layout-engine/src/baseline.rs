@@ -0,0 +1,59 @@
+//! First-baseline queries for aligning text across columns.
+//!
+//! Taffy uses baselines while laying out `align-items: baseline` lines but does not keep
+//! them, so they are derived again from the computed layouts. The engine has no text and no
+//! measure functions, so every baseline ultimately comes from a box without in-flow
+//! children, which per CSS synthesizes its baseline from its bottom edge.
+
+use taffy::prelude::*;
+
+use super::LayoutEngineState;
+
+impl LayoutEngineState {
+    /// Returns the first baseline of `node` as an offset from its top edge, from the last
+    /// compute. A container takes it from its first in-flow child that takes part in baseline
+    /// alignment, or from its first in-flow child if none does.
+    pub(crate) fn first_baseline(&self, node: NodeId) -> f32 {
+        let Ok(layout) = self.taffy.layout(node) else {
+            return 0.0;
+        };
+        let in_flow: Vec<NodeId> = self
+            .taffy
+            .child_ids(node)
+            .filter(|child| {
+                self.taffy.style(*child).is_ok_and(|style| {
+                    style.position != Position::Absolute && style.display != Display::None
+                })
+            })
+            .collect();
+        let Some(first) = in_flow
+            .iter()
+            .copied()
+            .find(|child| self.aligns_to_baseline(node, *child))
+            .or(in_flow.first().copied())
+        else {
+            return layout.size.height;
+        };
+
+        let child_top = self
+            .taffy
+            .layout(first)
+            .map_or(0.0, |child_layout| child_layout.location.y);
+        child_top + self.first_baseline(first)
+    }
+
+    fn aligns_to_baseline(&self, parent: NodeId, child: NodeId) -> bool {
+        let (Ok(parent_style), Ok(child_style)) =
+            (self.taffy.style(parent), self.taffy.style(child))
+        else {
+            return false;
+        };
+        let is_row = matches!(
+            parent_style.flex_direction,
+            FlexDirection::Row | FlexDirection::RowReverse
+        );
+        parent_style.display == Display::Flex
+            && is_row
+            && child_style.align_self.or(parent_style.align_items) == Some(AlignItems::Baseline)
+    }
+}
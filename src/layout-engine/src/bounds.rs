@@ -0,0 +1,37 @@
+//! Bounding boxes of node sets in root coordinates, for marquee selection and scrolling a
+//! group of nodes into view.
+//!
+//! Emitted locations are relative to each node's logical parent, so they are accumulated up
+//! the Taffy tree instead. That is the tree Taffy placed the nodes in, which already accounts
+//! for absolutely positioned and root-anchored nodes.
+
+use taffy::prelude::*;
+
+use super::LayoutEngineState;
+
+impl LayoutEngineState {
+    /// Returns the `[x, y, width, height]` union of `nodes`' rects from the last compute, in
+    /// the root's coordinates. An empty set has an empty box at the origin.
+    pub(crate) fn bounding_box(&self, nodes: &[NodeId]) -> [f32; 4] {
+        let mut bounds: Option<[f32; 4]> = None;
+        for node in nodes {
+            let Ok(layout) = self.taffy.layout(*node) else {
+                continue;
+            };
+            let origin = self.absolute_location(*node, false);
+            let (right, bottom) = (origin.x + layout.size.width, origin.y + layout.size.height);
+            bounds = Some(match bounds {
+                Some([left, top, max_x, max_y]) => [
+                    left.min(origin.x),
+                    top.min(origin.y),
+                    max_x.max(right),
+                    max_y.max(bottom),
+                ],
+                None => [origin.x, origin.y, right, bottom],
+            });
+        }
+        bounds.map_or([0.0; 4], |[left, top, right, bottom]| {
+            [left, top, right - left, bottom - top]
+        })
+    }
+}
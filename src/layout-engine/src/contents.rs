@@ -0,0 +1,167 @@
+//! `display: contents` pass-through nodes.
+//!
+//! Taffy has no `contents`, so such a node is kept in its parent's Taffy children as a
+//! `display: none` placeholder, immediately followed by its own children, which the engine
+//! remembers as its logical children. Its children are therefore laid out as children of the
+//! nearest ancestor that does generate a box, while the node itself keeps its js id and
+//! reports an empty rect. Nested contents nodes flatten the same way.
+
+use taffy::TaffyResult;
+use taffy::prelude::*;
+
+use super::{LayoutEngineState, StyleProp};
+
+impl LayoutEngineState {
+    pub(crate) fn is_contents_style(style_slice: &[f32]) -> bool {
+        style_slice[StyleProp::Display as usize] as i32 == 4
+    }
+
+    pub(crate) fn is_contents(&self, node: NodeId) -> bool {
+        self.contents_children.contains_key(&node)
+    }
+
+    /// Records a style's contents flag for a node that is not wired yet.
+    pub(crate) fn set_contents_flag(&mut self, node: NodeId, contents: bool) {
+        if !contents {
+            self.contents_children.remove(&node);
+        } else if !self.is_contents(node) {
+            self.contents_children.insert(node, Vec::new());
+        }
+    }
+
+    /// Applies a contents flag change from a style update, moving an already wired node's
+    /// children between itself and the node's parent.
+    pub(crate) fn update_contents_flag(&mut self, node: NodeId, contents: bool) -> TaffyResult<()> {
+        if self.is_contents(node) == contents {
+            return Ok(());
+        }
+
+        if contents {
+            let contents_parent = &self.contents_parent;
+            let children: Vec<NodeId> = self
+                .taffy
+                .child_ids(node)
+                .filter(|child| !contents_parent.contains_key(child))
+                .collect();
+            self.taffy.set_children(node, &[])?;
+            for child in &children {
+                self.contents_parent.insert(*child, node);
+            }
+            self.contents_children.insert(node, children);
+            self.respread_contents(node)
+        } else {
+            let children = self.contents_children.remove(&node).unwrap_or_default();
+            for child in &children {
+                self.contents_parent.remove(child);
+            }
+            // Taffy takes them, and any spliced grandchildren, back out of the old parent.
+            self.children_scratch.clear();
+            self.children_scratch.extend_from_slice(&children);
+            self.expand_contents_in_scratch();
+            self.taffy.set_children(node, &self.children_scratch)
+        }
+    }
+
+    /// Stores `children_scratch` as the children of the contents node `node` and splices them
+    /// into its parent.
+    pub(crate) fn wire_contents_children(&mut self, node: NodeId) -> TaffyResult<()> {
+        // The children stay wherever they were until the parent is respread, which does not
+        // happen while `node` itself is unwired.
+        let children = std::mem::take(&mut self.children_scratch);
+        let mut spliced = Vec::new();
+        for child in &children {
+            self.push_expanded(*child, &mut spliced);
+        }
+        for child in spliced {
+            if let Some(parent) = self.taffy.parent(child) {
+                self.taffy.remove_child(parent, child)?;
+            }
+        }
+        self.children_scratch = children;
+        for child in &self.children_scratch {
+            self.contents_parent.insert(*child, node);
+        }
+        self.contents_children
+            .insert(node, self.children_scratch.clone());
+        self.respread_contents(node)
+    }
+
+    /// Forgets that `child` was spliced out of a contents node, before it is wired elsewhere
+    /// or removed.
+    pub(crate) fn detach_from_contents(&mut self, child: NodeId) {
+        if let Some(node) = self.contents_parent.remove(&child)
+            && let Some(children) = self.contents_children.get_mut(&node)
+        {
+            children.retain(|other| *other != child);
+        }
+    }
+
+    /// Drops contents state that refers to `removed`, before it leaves the Taffy tree. Its
+    /// children stay, parentless, like those of any removed node.
+    pub(crate) fn forget_contents(&mut self, removed: NodeId) -> TaffyResult<()> {
+        self.detach_from_contents(removed);
+        let Some(children) = self.contents_children.remove(&removed) else {
+            return Ok(());
+        };
+        for child in children {
+            self.contents_parent.remove(&child);
+            let mut spliced = Vec::new();
+            self.push_expanded(child, &mut spliced);
+            for node in spliced {
+                if let Some(parent) = self.taffy.parent(node) {
+                    self.taffy.remove_child(parent, node)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Replaces each contents node in `children_scratch` by itself followed by its children,
+    /// recursively.
+    pub(crate) fn expand_contents_in_scratch(&mut self) {
+        if !self
+            .children_scratch
+            .iter()
+            .any(|child| self.is_contents(*child))
+        {
+            return;
+        }
+        let scratch = std::mem::take(&mut self.children_scratch);
+        let mut expanded = Vec::with_capacity(scratch.len());
+        for child in scratch {
+            self.push_expanded(child, &mut expanded);
+        }
+        self.children_scratch = expanded;
+    }
+
+    fn push_expanded(&self, node: NodeId, out: &mut Vec<NodeId>) {
+        out.push(node);
+        if let Some(children) = self.contents_children.get(&node) {
+            for child in children {
+                self.push_expanded(*child, out);
+            }
+        }
+    }
+
+    /// Rewires the Taffy parent of the contents node `node` after its children changed.
+    pub(crate) fn respread_contents(&mut self, node: NodeId) -> TaffyResult<()> {
+        let Some(parent) = self.taffy.parent(node) else {
+            return Ok(());
+        };
+        if self.nodes.get(&0) == Some(&parent) {
+            return self.sync_root_children();
+        }
+        if let Some(contents_node) = self.contents_parent.get(&node).copied() {
+            return self.respread_contents(contents_node);
+        }
+        let contents_parent = &self.contents_parent;
+        self.children_scratch.clear();
+        self.children_scratch.extend(
+            self.taffy
+                .child_ids(parent)
+                .filter(|child| !contents_parent.contains_key(child)),
+        );
+        self.expand_contents_in_scratch();
+        self.taffy.set_children(parent, &self.children_scratch)
+    }
+}
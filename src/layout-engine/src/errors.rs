@@ -0,0 +1,79 @@
+//! Status codes returned across the FFI boundary and their messages.
+//!
+//! 0 is success and every failure is negative. Engine errors use -1..=-99; failures reported
+//! by Taffy itself are mapped into the reserved -100..=-199 range.
+
+use std::ffi::CStr;
+
+use taffy::TaffyError;
+
+/// Maps a Taffy failure to its status code, one per `TaffyError` variant.
+pub(crate) fn taffy_error_code(error: &TaffyError) -> i32 {
+    match error {
+        TaffyError::ChildIndexOutOfBounds { .. } => -100,
+        TaffyError::InvalidParentNode(_) => -101,
+        TaffyError::InvalidChildNode(_) => -102,
+        TaffyError::InvalidInputNode(_) => -103,
+    }
+}
+
+/// Returns the message for `code`, or `None` for a code the engine never returns.
+pub(crate) fn error_message(code: i32) -> Option<&'static CStr> {
+    Some(match code {
+        0 => c"ok",
+        -1 => c"null engine pointer",
+        -2 => c"style buffer length is not a multiple of the style stride",
+        -4 => c"null nodes buffer",
+        -5 => c"null children buffer",
+        -6 => c"null ops buffer",
+        -7 => c"null op styles buffer",
+        -8 => c"null op children buffer",
+        -10 => c"truncated CreateLeaf op",
+        -11 => c"CreateLeaf style offset is out of range",
+        -12 => c"truncated UpdateStyle op",
+        -13 => c"UpdateStyle style offset is out of range",
+        -14 => c"UpdateStyle targets an unknown node",
+        -15 => c"truncated SetChildren op",
+        -16 => c"SetChildren targets an unknown node",
+        -17 => c"SetChildren range is out of the children buffer",
+        -18 => c"SetChildren references an unknown child",
+        -19 => c"truncated RemoveNode op",
+        -20 => c"unknown opcode",
+        -21 => c"unknown node id",
+        -22 => c"null JSON buffer",
+        -23 => c"invalid JSON",
+        -24 => c"null measure output",
+        -25 => c"invalid intrinsic size mode",
+        -26 => c"null timings output",
+        -27 => c"null cache stats output",
+        -28 => c"infinite style value",
+        -29 => c"null invalid style output",
+        -30 => c"no style value was rejected",
+        -31 => c"output buffer is too small",
+        -32 => c"null output buffer",
+        -33 => c"invalid available space",
+        -34 => c"null layout output",
+        -35 => c"children range is out of the children buffer",
+        -36 => c"no node with id 0 to compute from",
+        -37 => c"caller style stride does not match the library's",
+        -38 => c"null baseline output",
+        -39 => c"truncated RemoveSubtree op",
+        -40 => c"truncated SetStylePartial op",
+        -41 => c"SetStylePartial pairs are out of the styles buffer",
+        -42 => c"SetStylePartial targets an unknown node",
+        -43 => c"SetStylePartial names an unknown style lane",
+        -44 => c"null ids buffer",
+        -45 => c"ids buffer length does not match the node count",
+        -46 => c"ids buffer repeats an id",
+        -47 => c"null results pointer or length output",
+        -48 => c"null bounding box output",
+        -49 => c"null hit test output",
+        -50 => c"children list repeats a child",
+        -51 => c"node id already exists",
+        -100 => c"taffy: child index out of bounds",
+        -101 => c"taffy: invalid parent node",
+        -102 => c"taffy: invalid child node",
+        -103 => c"taffy: invalid input node",
+        _ => return None,
+    })
+}
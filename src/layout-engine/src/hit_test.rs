@@ -0,0 +1,55 @@
+//! Hit testing: which node is drawn at a point.
+//!
+//! The computed tree is walked from the top of the paint order down. Like CSS, positioned
+//! children paint over in-flow ones and later siblings over earlier ones, and children may
+//! overflow their parent unless it clips. `display: none` subtrees are skipped, and a
+//! `visibility: hidden` node is never hit itself, though its visible descendants can be.
+
+use taffy::prelude::*;
+use taffy::{Overflow, Point};
+
+use super::LayoutEngineState;
+
+impl LayoutEngineState {
+    /// Returns the topmost, deepest node of `root`'s subtree whose rect from the last compute
+    /// contains `point`, given in root coordinates.
+    pub(crate) fn hit_test(&self, root: NodeId, point: Point<f32>) -> Option<NodeId> {
+        let origin = self.absolute_location(root, false);
+        self.hit_test_from(root, origin, point)
+    }
+
+    fn hit_test_from(&self, node: NodeId, origin: Point<f32>, point: Point<f32>) -> Option<NodeId> {
+        let style = self.taffy.style(node).ok()?;
+        if style.display == Display::None {
+            return None;
+        }
+        let size = self.taffy.layout(node).ok()?.size;
+        let inside = point.x >= origin.x
+            && point.x < origin.x + size.width
+            && point.y >= origin.y
+            && point.y < origin.y + size.height;
+        let clips = style.overflow.x != Overflow::Visible || style.overflow.y != Overflow::Visible;
+
+        if inside || !clips {
+            let (positioned, in_flow): (Vec<NodeId>, Vec<NodeId>) =
+                self.taffy.child_ids(node).partition(|child| {
+                    self.taffy
+                        .style(*child)
+                        .is_ok_and(|style| style.position == Position::Absolute)
+                });
+            for child in positioned.iter().rev().chain(in_flow.iter().rev()) {
+                let Ok(layout) = self.taffy.layout(*child) else {
+                    continue;
+                };
+                let child_origin = Point {
+                    x: origin.x + layout.location.x,
+                    y: origin.y + layout.location.y,
+                };
+                if let Some(hit) = self.hit_test_from(*child, child_origin, point) {
+                    return Some(hit);
+                }
+            }
+        }
+        (inside && self.is_visible(node)).then_some(node)
+    }
+}
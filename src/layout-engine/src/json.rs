@@ -0,0 +1,319 @@
+//! JSON input for embedders that would rather not encode the flat buffers by hand.
+//!
+//! The accepted shape mirrors the TS `LayoutInputNode`: a nested tree of camelCase style
+//! fields plus `children`. Nodes are encoded into the same flat buffers the TS binding
+//! produces, with js ids assigned in depth-first pre-order (the root is 0).
+
+use serde::Deserialize;
+
+use super::{STYLE_STRIDE, StyleProp};
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonDimension {
+    Points(f32),
+    // "auto" and anything else the buffer path cannot express.
+    Keyword(String),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonBox {
+    Uniform(f32),
+    // [left, right, top, bottom], matching the buffer lane order.
+    Sides([f32; 4]),
+}
+
+#[derive(Deserialize)]
+enum JsonAuto {
+    #[serde(rename = "auto")]
+    Auto,
+}
+
+// A margin side is a number or "auto"; any other string fails to parse.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonMarginSide {
+    Points(f32),
+    Auto(JsonAuto),
+}
+
+// Like `JsonBox`, but each side may also be "auto".
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonMargin {
+    Uniform(JsonMarginSide),
+    Sides([JsonMarginSide; 4]),
+}
+
+// `width` is the column gap and `height` the row gap, as in Taffy's `Style::gap`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonGap {
+    Uniform(JsonGapValue),
+    Axes {
+        width: Option<JsonGapValue>,
+        height: Option<JsonGapValue>,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonGapValue {
+    Points(f32),
+    // "10%"; anything else counts as 0.
+    Keyword(String),
+}
+
+/// Returns the (value, unit) lanes for a flex basis: points, "10%", or NaN for "auto".
+fn basis_lanes(value: &Option<JsonDimension>) -> (f32, f32) {
+    match value {
+        Some(JsonDimension::Points(v)) => (*v, 0.0),
+        Some(JsonDimension::Keyword(keyword)) => keyword
+            .strip_suffix('%')
+            .and_then(|number| number.trim().parse().ok())
+            .map_or((f32::NAN, 0.0), |v| (v, 1.0)),
+        None => (f32::NAN, 0.0),
+    }
+}
+
+/// Returns the (value, unit) lanes for one gap axis.
+fn gap_lanes(value: Option<&JsonGapValue>) -> (f32, f32) {
+    match value {
+        Some(JsonGapValue::Points(v)) => (*v, 0.0),
+        Some(JsonGapValue::Keyword(keyword)) => keyword
+            .strip_suffix('%')
+            .and_then(|number| number.trim().parse().ok())
+            .map_or((0.0, 0.0), |v| (v, 1.0)),
+        None => (0.0, 0.0),
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct JsonNode {
+    display: Option<String>,
+    position: Option<String>,
+    width: Option<JsonDimension>,
+    height: Option<JsonDimension>,
+    min_width: Option<JsonDimension>,
+    min_height: Option<JsonDimension>,
+    max_width: Option<JsonDimension>,
+    max_height: Option<JsonDimension>,
+    aspect_ratio: Option<f32>,
+    visibility: Option<String>,
+    padding: Option<JsonBox>,
+    margin: Option<JsonMargin>,
+    flex_direction: Option<String>,
+    flex_wrap: Option<String>,
+    flex_grow: Option<f32>,
+    flex_shrink: Option<f32>,
+    flex_basis: Option<JsonDimension>,
+    justify_content: Option<String>,
+    align_items: Option<String>,
+    align_content: Option<String>,
+    align_self: Option<String>,
+    justify_items: Option<String>,
+    justify_self: Option<String>,
+    gap: Option<JsonGap>,
+    overflow: Option<String>,
+    overflow_x: Option<String>,
+    overflow_y: Option<String>,
+    scrollbar_width: Option<f32>,
+    box_sizing: Option<String>,
+    direction: Option<String>,
+    anchor_root: bool,
+    order: Option<f32>,
+    children: Vec<JsonNode>,
+}
+
+fn dimension_to_f32(dim: &Option<JsonDimension>) -> f32 {
+    match dim {
+        Some(JsonDimension::Points(value)) => *value,
+        _ => f32::NAN,
+    }
+}
+
+fn box_to_quad(value: &Option<JsonBox>) -> [f32; 4] {
+    match value {
+        Some(JsonBox::Uniform(v)) => [*v; 4],
+        Some(JsonBox::Sides(sides)) => *sides,
+        None => [0.0; 4],
+    }
+}
+
+fn margin_to_quad(value: &Option<JsonMargin>) -> [f32; 4] {
+    let side = |side: &JsonMarginSide| match side {
+        JsonMarginSide::Points(value) => *value,
+        JsonMarginSide::Auto(JsonAuto::Auto) => f32::NAN,
+    };
+    match value {
+        Some(JsonMargin::Uniform(dim)) => [side(dim); 4],
+        Some(JsonMargin::Sides(sides)) => sides.each_ref().map(side),
+        None => [0.0; 4],
+    }
+}
+
+fn code(value: Option<&str>, codes: &[&str], default: f32) -> f32 {
+    value
+        .and_then(|v| codes.iter().position(|c| *c == v))
+        .map_or(default, |i| i as f32)
+}
+
+impl JsonNode {
+    fn write_style(&self, out: &mut [f32]) {
+        out[StyleProp::Display as usize] = code(
+            self.display.as_deref(),
+            &["flex", "none", "block", "grid", "contents"],
+            0.0,
+        );
+        out[StyleProp::FlexGrow as usize] = self.flex_grow.unwrap_or(0.0);
+        out[StyleProp::FlexShrink as usize] = self.flex_shrink.unwrap_or(1.0);
+        (
+            out[StyleProp::FlexBasis as usize],
+            out[StyleProp::FlexBasisUnit as usize],
+        ) = basis_lanes(&self.flex_basis);
+        out[StyleProp::FlexDirection as usize] = code(
+            self.flex_direction.as_deref(),
+            &["row", "column", "row-reverse", "column-reverse"],
+            0.0,
+        );
+        out[StyleProp::FlexWrap as usize] = code(
+            self.flex_wrap.as_deref(),
+            &["nowrap", "wrap", "wrap-reverse"],
+            0.0,
+        );
+
+        let (gap_row, gap_column) = match &self.gap {
+            Some(JsonGap::Uniform(v)) => (gap_lanes(Some(v)), gap_lanes(Some(v))),
+            Some(JsonGap::Axes { width, height }) => {
+                (gap_lanes(height.as_ref()), gap_lanes(width.as_ref()))
+            }
+            None => (gap_lanes(None), gap_lanes(None)),
+        };
+        (
+            out[StyleProp::GapRow as usize],
+            out[StyleProp::GapRowUnit as usize],
+        ) = gap_row;
+        (
+            out[StyleProp::GapColumn as usize],
+            out[StyleProp::GapColumnUnit as usize],
+        ) = gap_column;
+
+        out[StyleProp::JustifyContent as usize] = code(
+            self.justify_content.as_deref(),
+            &[
+                "flex-start",
+                "flex-end",
+                "center",
+                "space-between",
+                "space-around",
+                "space-evenly",
+            ],
+            0.0,
+        );
+        out[StyleProp::AlignItems as usize] = code(
+            self.align_items.as_deref(),
+            &["flex-start", "flex-end", "center", "baseline", "stretch"],
+            4.0,
+        );
+        out[StyleProp::AlignContent as usize] = code(
+            self.align_content.as_deref(),
+            &[
+                "stretch",
+                "flex-start",
+                "flex-end",
+                "center",
+                "space-between",
+                "space-around",
+                "space-evenly",
+            ],
+            0.0,
+        );
+        out[StyleProp::AlignSelf as usize] = code(
+            self.align_self.as_deref(),
+            &[
+                "auto",
+                "flex-start",
+                "flex-end",
+                "center",
+                "baseline",
+                "stretch",
+            ],
+            0.0,
+        );
+        out[StyleProp::JustifyItems as usize] = code(
+            self.justify_items.as_deref(),
+            &["normal", "start", "end", "center", "stretch"],
+            0.0,
+        );
+        out[StyleProp::JustifySelf as usize] = code(
+            self.justify_self.as_deref(),
+            &["auto", "start", "end", "center", "stretch"],
+            0.0,
+        );
+        out[StyleProp::PositionType as usize] =
+            code(self.position.as_deref(), &["relative", "absolute"], 0.0);
+
+        out[StyleProp::Width as usize] = dimension_to_f32(&self.width);
+        out[StyleProp::Height as usize] = dimension_to_f32(&self.height);
+        out[StyleProp::MinWidth as usize] = dimension_to_f32(&self.min_width);
+        out[StyleProp::MinHeight as usize] = dimension_to_f32(&self.min_height);
+        out[StyleProp::MaxWidth as usize] = dimension_to_f32(&self.max_width);
+        out[StyleProp::MaxHeight as usize] = dimension_to_f32(&self.max_height);
+
+        let margin = margin_to_quad(&self.margin);
+        out[StyleProp::MarginLeft as usize..=StyleProp::MarginBottom as usize]
+            .copy_from_slice(&margin);
+        let padding = box_to_quad(&self.padding);
+        out[StyleProp::PaddingLeft as usize..=StyleProp::PaddingBottom as usize]
+            .copy_from_slice(&padding);
+
+        let overflow_codes = ["visible", "clip", "hidden", "scroll"];
+        let overflow = self.overflow.as_deref();
+        out[StyleProp::OverflowX as usize] = code(
+            self.overflow_x.as_deref().or(overflow),
+            &overflow_codes,
+            0.0,
+        );
+        out[StyleProp::OverflowY as usize] = code(
+            self.overflow_y.as_deref().or(overflow),
+            &overflow_codes,
+            0.0,
+        );
+        out[StyleProp::ScrollbarWidth as usize] = self.scrollbar_width.unwrap_or(0.0);
+        out[StyleProp::BoxSizing as usize] = code(
+            self.box_sizing.as_deref(),
+            &["border-box", "content-box"],
+            0.0,
+        );
+        out[StyleProp::Direction as usize] = code(self.direction.as_deref(), &["ltr", "rtl"], 0.0);
+        out[StyleProp::AnchorRoot as usize] = if self.anchor_root { 1.0 } else { 0.0 };
+        out[StyleProp::Order as usize] = self.order.unwrap_or(0.0);
+        out[StyleProp::AspectRatio as usize] = self.aspect_ratio.unwrap_or(0.0);
+        out[StyleProp::Visibility as usize] = code(
+            self.visibility.as_deref(),
+            &["inherit", "hidden", "visible"],
+            0.0,
+        );
+    }
+
+    /// Appends this node and its descendants to the flat buffers and returns its js id.
+    pub(crate) fn encode(&self, nodes: &mut Vec<f32>, children: &mut Vec<u32>) -> u32 {
+        let id = nodes.len() / STYLE_STRIDE;
+        nodes.resize(nodes.len() + STYLE_STRIDE, 0.0);
+        self.write_style(&mut nodes[id * STYLE_STRIDE..(id + 1) * STYLE_STRIDE]);
+
+        let child_ids: Vec<u32> = self
+            .children
+            .iter()
+            .map(|child| child.encode(nodes, children))
+            .collect();
+
+        let style = &mut nodes[id * STYLE_STRIDE..(id + 1) * STYLE_STRIDE];
+        style[StyleProp::ChildrenOffset as usize] = children.len() as f32;
+        style[StyleProp::ChildrenCount as usize] = child_ids.len() as f32;
+        children.extend(child_ids);
+        id as u32
+    }
+}
@@ -1,9 +1,31 @@
 #![allow(dead_code)]
-use std::collections::HashMap;
+#![allow(clippy::missing_safety_doc)]
+use std::collections::{HashMap, HashSet};
+use std::ffi::c_char;
+use std::fmt::Write;
+use std::time::{Duration, Instant};
 use taffy::prelude::*;
+use taffy::{Overflow, Point};
 
-#[repr(C)]
-enum StyleProp {
+use errors::taffy_error_code;
+
+mod anchor;
+mod baseline;
+mod bounds;
+mod contents;
+mod errors;
+mod hit_test;
+mod json;
+mod lines;
+mod ops;
+mod order;
+mod snapshot;
+mod visibility;
+mod zero_size;
+
+/// Lane of a node's style slice; `layout_engine_style_layout` lists them in this order.
+#[repr(u32)]
+pub enum StyleProp {
     Display,
     PositionType,
     FlexDirection,
@@ -32,13 +54,76 @@ enum StyleProp {
     GapColumn,
     ChildrenCount,
     ChildrenOffset,
+    OverflowX,
+    OverflowY,
+    ScrollbarWidth,
+    BoxSizing,
+    Direction,
+    AnchorRoot,
+    AlignContent,
+    JustifyItems,
+    JustifySelf,
+    GapRowUnit,
+    GapColumnUnit,
+    Order,
+    AspectRatio,
+    Visibility,
+    FlexBasisUnit,
     TotalProps,
 }
 const STYLE_STRIDE: usize = StyleProp::TotalProps as usize;
+
+// Offset table written by `layout_engine_style_layout`, in `StyleProp` declaration order.
+const STYLE_PROP_TABLE: [StyleProp; STYLE_STRIDE] = [
+    StyleProp::Display,
+    StyleProp::PositionType,
+    StyleProp::FlexDirection,
+    StyleProp::FlexWrap,
+    StyleProp::JustifyContent,
+    StyleProp::AlignItems,
+    StyleProp::AlignSelf,
+    StyleProp::FlexGrow,
+    StyleProp::FlexShrink,
+    StyleProp::FlexBasis,
+    StyleProp::Width,
+    StyleProp::Height,
+    StyleProp::MinWidth,
+    StyleProp::MinHeight,
+    StyleProp::MaxWidth,
+    StyleProp::MaxHeight,
+    StyleProp::MarginLeft,
+    StyleProp::MarginRight,
+    StyleProp::MarginTop,
+    StyleProp::MarginBottom,
+    StyleProp::PaddingLeft,
+    StyleProp::PaddingRight,
+    StyleProp::PaddingTop,
+    StyleProp::PaddingBottom,
+    StyleProp::GapRow,
+    StyleProp::GapColumn,
+    StyleProp::ChildrenCount,
+    StyleProp::ChildrenOffset,
+    StyleProp::OverflowX,
+    StyleProp::OverflowY,
+    StyleProp::ScrollbarWidth,
+    StyleProp::BoxSizing,
+    StyleProp::Direction,
+    StyleProp::AnchorRoot,
+    StyleProp::AlignContent,
+    StyleProp::JustifyItems,
+    StyleProp::JustifySelf,
+    StyleProp::GapRowUnit,
+    StyleProp::GapColumnUnit,
+    StyleProp::Order,
+    StyleProp::AspectRatio,
+    StyleProp::Visibility,
+    StyleProp::FlexBasisUnit,
+];
 const RESULT_STRIDE: usize = 5; // js_id, x, y, width, height
+const DEFAULT_NODE_CAPACITY: usize = 15000;
 
 // Increment this when changing any exported FFI surface or buffer layout.
-const LAYOUT_ENGINE_ABI_VERSION: u32 = 2;
+pub const LAYOUT_ENGINE_ABI_VERSION: u32 = 59;
 
 #[repr(u32)]
 enum OpCode {
@@ -46,25 +131,210 @@ enum OpCode {
     UpdateStyle = 2,
     SetChildren = 3,
     RemoveNode = 4,
+    RemoveSubtree = 5,
+    SetStylePartial = 6,
+}
+
+/// Per-axis available space for the computes and measurements that take explicit modes.
+#[repr(u32)]
+enum AvailableSpaceMode {
+    MaxContent = 0,
+    MinContent = 1,
+    Definite = 2,
+}
+
+/// Phase durations of the last compute, recorded only while timings are enabled.
+#[derive(Clone, Copy, Default)]
+struct LayoutTimings {
+    node_count: usize,
+    build: Duration,
+    compute: Duration,
+    emit: Duration,
+}
+
+/// Cache reuse of the last compute, approximated from dirty flags: a node that was clean
+/// before the compute counts as a hit, a dirty one as a miss.
+#[derive(Clone, Copy, Default)]
+struct CacheStats {
+    hits: usize,
+    misses: usize,
 }
 
+#[derive(Clone)]
 pub struct LayoutEngineState {
     taffy: TaffyTree,
     nodes: HashMap<u32, NodeId>,
     node_id_map: HashMap<NodeId, u32>,
     results_buffer: Vec<f32>,
+    // Reused by child wiring so SetChildren doesn't allocate per op.
+    children_scratch: Vec<NodeId>,
+    // Reused to emit results in ascending js id order.
+    emit_order: Vec<(u32, NodeId)>,
+    // Root and available space of the last compute, replayed after intrinsic measurement.
+    last_layout: Option<(NodeId, Size<AvailableSpace>)>,
+    timings_enabled: bool,
+    // Leave `display: none` nodes and their descendants out of the results.
+    skip_hidden: bool,
+    // Report zero-sized nodes at their flow position, see `zero_size.rs`.
+    zero_size_at_flow: bool,
+    last_timings: LayoutTimings,
+    cache_stats: CacheStats,
+    // (js id, style lane) of the infinite value that failed the last call, if any.
+    invalid_style: Option<(u32, u32)>,
+    // Nodes whose style anchors them to the root, see `anchor.rs`.
+    anchor_flags: HashSet<NodeId>,
+    // Anchored node -> (logical parent, slot in the logical parent's children).
+    anchored: HashMap<NodeId, (NodeId, usize)>,
+    // Node 0's own children, without the anchored nodes appended after them.
+    root_flow_children: Vec<NodeId>,
+    // Contents node -> its logical children, see `contents.rs`.
+    contents_children: HashMap<NodeId, Vec<NodeId>>,
+    // Child spliced out of a contents node -> that node.
+    contents_parent: HashMap<NodeId, NodeId>,
+    // Style lanes each node was last given, kept so the input tree can be exported.
+    style_lanes: HashMap<NodeId, [f32; STYLE_STRIDE]>,
+    // Rect each js id was last emitted with, survives rebuilds to diff consecutive frames.
+    last_rects: HashMap<u32, [f32; 4]>,
+    // Ids whose emitted rect differs from the previous frame, in emission order.
+    changed_ids: Vec<u32>,
+    // Each wired child's slot in the children list its host gave, see `order.rs`.
+    host_slots: HashMap<NodeId, usize>,
 }
 
 impl LayoutEngineState {
     fn new() -> Self {
+        Self::with_capacity(DEFAULT_NODE_CAPACITY)
+    }
+
+    fn with_capacity(node_capacity: usize) -> Self {
         Self {
-            taffy: TaffyTree::with_capacity(15000),
-            nodes: HashMap::with_capacity(15000),
-            node_id_map: HashMap::with_capacity(15000),
-            results_buffer: Vec::with_capacity(15000 * 5),
+            taffy: TaffyTree::with_capacity(node_capacity),
+            nodes: HashMap::with_capacity(node_capacity),
+            node_id_map: HashMap::with_capacity(node_capacity),
+            results_buffer: Vec::with_capacity(node_capacity * RESULT_STRIDE),
+            children_scratch: Vec::new(),
+            emit_order: Vec::with_capacity(node_capacity),
+            last_layout: None,
+            timings_enabled: false,
+            skip_hidden: false,
+            zero_size_at_flow: false,
+            last_timings: LayoutTimings::default(),
+            cache_stats: CacheStats::default(),
+            invalid_style: None,
+            anchor_flags: HashSet::new(),
+            anchored: HashMap::new(),
+            root_flow_children: Vec::new(),
+            contents_children: HashMap::new(),
+            contents_parent: HashMap::new(),
+            style_lanes: HashMap::with_capacity(node_capacity),
+            last_rects: HashMap::with_capacity(node_capacity),
+            changed_ids: Vec::new(),
+            host_slots: HashMap::with_capacity(node_capacity),
+        }
+    }
+
+    /// Drops every node but keeps the allocated capacity for reuse. Rebuilds keep
+    /// `last_rects` so they still report only the rects that moved.
+    fn reset(&mut self) {
+        self.taffy.clear();
+        self.nodes.clear();
+        self.node_id_map.clear();
+        self.results_buffer.clear();
+        self.last_layout = None;
+        self.anchor_flags.clear();
+        self.anchored.clear();
+        self.root_flow_children.clear();
+        self.contents_children.clear();
+        self.contents_parent.clear();
+        self.style_lanes.clear();
+        self.host_slots.clear();
+    }
+
+    /// Releases spare capacity from buffers that are less than a quarter utilized.
+    fn trim_memory(&mut self) {
+        if self.results_buffer.len() * 4 < self.results_buffer.capacity() {
+            self.results_buffer.shrink_to_fit();
+        }
+        if self.nodes.len() * 4 < self.nodes.capacity() {
+            self.nodes.shrink_to_fit();
+        }
+        if self.node_id_map.len() * 4 < self.node_id_map.capacity() {
+            self.node_id_map.shrink_to_fit();
+        }
+        if self.style_lanes.len() * 4 < self.style_lanes.capacity() {
+            self.style_lanes.shrink_to_fit();
+        }
+        if self.host_slots.len() * 4 < self.host_slots.capacity() {
+            self.host_slots.shrink_to_fit();
+        }
+        if self.nodes.len() * 4 < self.emit_order.capacity() {
+            self.emit_order.clear();
+            self.emit_order.shrink_to_fit();
+        }
+    }
+
+    /// Starts a phase timer, or does nothing while timings are disabled.
+    fn start_timer(&self) -> Option<Instant> {
+        self.timings_enabled.then(Instant::now)
+    }
+
+    fn record_build(&mut self, build_start: Option<Instant>) {
+        if let Some(start) = build_start {
+            self.last_timings.build = start.elapsed();
+        }
+    }
+
+    fn record_compute_and_emit(
+        &mut self,
+        compute_start: Option<Instant>,
+        emit_start: Option<Instant>,
+    ) {
+        if let (Some(compute_start), Some(emit_start)) = (compute_start, emit_start) {
+            self.last_timings.node_count = self.results_buffer.len() / RESULT_STRIDE;
+            self.last_timings.compute = emit_start - compute_start;
+            self.last_timings.emit = emit_start.elapsed();
         }
     }
 
+    /// Counts clean and dirty nodes in the subtrees about to be laid out from `roots`.
+    fn record_cache_stats(&mut self, roots: &[NodeId]) {
+        let mut stats = CacheStats::default();
+        let mut stack = roots.to_vec();
+        while let Some(node) = stack.pop() {
+            if self.taffy.dirty(node).unwrap_or(true) {
+                stats.misses += 1;
+            } else {
+                stats.hits += 1;
+            }
+            stack.extend(self.taffy.child_ids(node));
+        }
+        self.cache_stats = stats;
+    }
+
+    /// Records the first infinite lane of `style_slice` against `node_id`. NaN is left
+    /// alone since several lanes use it to mean "unset".
+    fn check_finite(&mut self, node_id: u32, style_slice: &[f32]) -> bool {
+        match style_slice.iter().position(|value| value.is_infinite()) {
+            Some(prop) => {
+                self.invalid_style = Some((node_id, prop as u32));
+                false
+            }
+            None => true,
+        }
+    }
+
+    fn record_style_lanes(&mut self, node: NodeId, style_slice: &[f32]) {
+        let mut lanes = [0.0; STYLE_STRIDE];
+        lanes.copy_from_slice(style_slice);
+        self.style_lanes.insert(node, lanes);
+    }
+
+    /// Root anchoring only applies to absolutely positioned nodes.
+    fn anchors_to_root(style_slice: &[f32]) -> bool {
+        style_slice[StyleProp::AnchorRoot as usize] as i32 == 1
+            && style_slice[StyleProp::PositionType as usize] as i32 == 1
+    }
+
     fn style_from_slice(style_slice: &[f32]) -> Style {
         let mut style = Style::default();
 
@@ -78,6 +348,30 @@ impl LayoutEngineState {
             style.size.height = length(height);
         }
 
+        style.min_size = Size {
+            width: dimension_from_f32(style_slice[StyleProp::MinWidth as usize]),
+            height: dimension_from_f32(style_slice[StyleProp::MinHeight as usize]),
+        };
+        style.max_size = Size {
+            width: dimension_from_f32(style_slice[StyleProp::MaxWidth as usize]),
+            height: dimension_from_f32(style_slice[StyleProp::MaxHeight as usize]),
+        };
+
+        // Width over height; 0 and NaN leave it unset, as does any other non-positive ratio.
+        let aspect_ratio = style_slice[StyleProp::AspectRatio as usize];
+        if aspect_ratio > 0.0 {
+            style.aspect_ratio = Some(aspect_ratio);
+        }
+
+        style.display = match style_slice[StyleProp::Display as usize] as i32 {
+            1 => Display::None,
+            2 => Display::Block,
+            3 => Display::Grid,
+            // `contents`: a placeholder followed by its spliced children, see `contents.rs`.
+            4 => Display::None,
+            _ => Display::Flex,
+        };
+
         style.flex_direction = match style_slice[StyleProp::FlexDirection as usize] as i32 {
             1 => FlexDirection::Column,
             2 => FlexDirection::RowReverse,
@@ -85,21 +379,57 @@ impl LayoutEngineState {
             _ => FlexDirection::Row,
         };
 
+        style.flex_wrap = match style_slice[StyleProp::FlexWrap as usize] as i32 {
+            1 => FlexWrap::Wrap,
+            2 => FlexWrap::WrapReverse,
+            _ => FlexWrap::NoWrap,
+        };
+
+        // Taffy has no writing direction, so RTL is expressed by flipping the inline axis.
+        // `FlexStart`/`FlexEnd` are relative to the main axis and follow along.
+        if style_slice[StyleProp::Direction as usize] as i32 == 1 {
+            style.flex_direction = match style.flex_direction {
+                FlexDirection::Row => FlexDirection::RowReverse,
+                FlexDirection::RowReverse => FlexDirection::Row,
+                other => other,
+            };
+        }
+
+        // Column gaps separate items horizontally, so they are Taffy's width; row gaps height.
         style.gap = Size {
-            width: length(style_slice[StyleProp::GapColumn as usize]),
-            height: length(style_slice[StyleProp::GapRow as usize]),
+            width: gap_from_lanes(
+                style_slice[StyleProp::GapColumn as usize],
+                style_slice[StyleProp::GapColumnUnit as usize],
+            ),
+            height: gap_from_lanes(
+                style_slice[StyleProp::GapRow as usize],
+                style_slice[StyleProp::GapRowUnit as usize],
+            ),
         };
 
-        style.justify_content = Some(
-            match style_slice[StyleProp::JustifyContent as usize] as i32 {
-                1 => JustifyContent::FlexEnd,
-                2 => JustifyContent::Center,
-                3 => JustifyContent::SpaceBetween,
-                4 => JustifyContent::SpaceAround,
-                5 => JustifyContent::SpaceEvenly,
-                _ => JustifyContent::FlexStart,
-            },
-        );
+        // 0 is flex-start, except that grid leaves it unset to stretch auto tracks across the
+        // container. Taffy falls back to `Start`, not `FlexStart`, for an unset value when
+        // placing absolutely positioned flex children, which differs under wrap-reverse.
+        style.justify_content = match style_slice[StyleProp::JustifyContent as usize] as i32 {
+            1 => Some(JustifyContent::FlexEnd),
+            2 => Some(JustifyContent::Center),
+            3 => Some(JustifyContent::SpaceBetween),
+            4 => Some(JustifyContent::SpaceAround),
+            5 => Some(JustifyContent::SpaceEvenly),
+            _ if style.display == Display::Grid => None,
+            _ => Some(JustifyContent::FlexStart),
+        };
+
+        // 0 leaves Taffy's default, which stretches lines like CSS `normal`.
+        style.align_content = match style_slice[StyleProp::AlignContent as usize] as i32 {
+            1 => Some(AlignContent::FlexStart),
+            2 => Some(AlignContent::FlexEnd),
+            3 => Some(AlignContent::Center),
+            4 => Some(AlignContent::SpaceBetween),
+            5 => Some(AlignContent::SpaceAround),
+            6 => Some(AlignContent::SpaceEvenly),
+            _ => None,
+        };
 
         style.align_items = Some(match style_slice[StyleProp::AlignItems as usize] as i32 {
             0 => AlignItems::FlexStart,
@@ -109,19 +439,52 @@ impl LayoutEngineState {
             _ => AlignItems::Stretch,
         });
 
+        style.align_self = match style_slice[StyleProp::AlignSelf as usize] as i32 {
+            1 => Some(AlignSelf::FlexStart),
+            2 => Some(AlignSelf::FlexEnd),
+            3 => Some(AlignSelf::Center),
+            4 => Some(AlignSelf::Baseline),
+            5 => Some(AlignSelf::Stretch),
+            _ => None,
+        };
+
+        // Only grid layout reads the inline-axis alignments; flex and block ignore them.
+        style.justify_items = justify_from_f32(style_slice[StyleProp::JustifyItems as usize]);
+        style.justify_self = justify_from_f32(style_slice[StyleProp::JustifySelf as usize]);
+
         style.position = match style_slice[StyleProp::PositionType as usize] as i32 {
             1 => Position::Absolute,
             _ => Position::Relative,
         };
 
+        style.overflow = Point {
+            x: overflow_from_f32(style_slice[StyleProp::OverflowX as usize]),
+            y: overflow_from_f32(style_slice[StyleProp::OverflowY as usize]),
+        };
+        style.scrollbar_width = style_slice[StyleProp::ScrollbarWidth as usize];
+
+        style.box_sizing = match style_slice[StyleProp::BoxSizing as usize] as i32 {
+            1 => BoxSizing::ContentBox,
+            _ => BoxSizing::BorderBox,
+        };
+
         style.flex_grow = style_slice[StyleProp::FlexGrow as usize];
         style.flex_shrink = style_slice[StyleProp::FlexShrink as usize];
+        // NaN is `auto`, like the width and height lanes; unit 1 is a percentage, as for gaps.
+        let flex_basis = style_slice[StyleProp::FlexBasis as usize];
+        if !flex_basis.is_nan() {
+            style.flex_basis = if style_slice[StyleProp::FlexBasisUnit as usize] as i32 == 1 {
+                percent(flex_basis / 100.0)
+            } else {
+                length(flex_basis)
+            };
+        }
 
         style.margin = Rect {
-            left: length(style_slice[StyleProp::MarginLeft as usize]),
-            right: length(style_slice[StyleProp::MarginRight as usize]),
-            top: length(style_slice[StyleProp::MarginTop as usize]),
-            bottom: length(style_slice[StyleProp::MarginBottom as usize]),
+            left: margin_from_f32(style_slice[StyleProp::MarginLeft as usize]),
+            right: margin_from_f32(style_slice[StyleProp::MarginRight as usize]),
+            top: margin_from_f32(style_slice[StyleProp::MarginTop as usize]),
+            bottom: margin_from_f32(style_slice[StyleProp::MarginBottom as usize]),
         };
         style.padding = Rect {
             left: length(style_slice[StyleProp::PaddingLeft as usize]),
@@ -133,21 +496,480 @@ impl LayoutEngineState {
         style
     }
 
-    fn compute_results(&mut self, root_node: NodeId) {
-        self.taffy
-            .compute_layout(root_node, Size::MAX_CONTENT)
-            .unwrap();
+    /// Rebuilds the whole tree from flat buffers and computes it from node 0. Node ids are
+    /// array positions unless `ids` gives one per node.
+    fn compute_from_buffers(
+        &mut self,
+        nodes_buffer: &[f32],
+        children_buffer: &[u32],
+        ids: Option<&[u32]>,
+    ) -> i32 {
+        let build_start = self.start_timer();
+        let status = self.build_from_buffers(nodes_buffer, children_buffer, ids);
+        if status != 0 {
+            return status;
+        }
+
+        self.record_build(build_start);
+        self.compute_from_root(Size::MAX_CONTENT)
+    }
+
+    /// Computes the tree from node 0. An empty tree is not an error: it computes to empty
+    /// results, so hosts can start from nothing.
+    fn compute_from_root(&mut self, available: Size<AvailableSpace>) -> i32 {
+        let Some(root_node) = self.nodes.get(&0).copied() else {
+            let status = Self::missing_root(self.nodes.keys().copied());
+            if status == 0 {
+                self.results_buffer.clear();
+                self.last_layout = None;
+                self.record_changes();
+            }
+            return status;
+        };
+        self.compute_results(root_node, available)
+    }
+
+    /// Status for a tree without node 0: 0 if there are no nodes at all, -36 if there are
+    /// (typically ids numbered from 1). Debug builds also log the ids that do exist.
+    fn missing_root(ids: impl Iterator<Item = u32>) -> i32 {
+        let mut ids: Vec<u32> = ids.collect();
+        if ids.is_empty() {
+            return 0;
+        }
+        if cfg!(debug_assertions) {
+            ids.sort_unstable();
+            eprintln!("layout-engine: no node with id 0 to compute from; node ids are {ids:?}");
+        }
+        -36
+    }
+
+    /// Rebuilds the whole tree from flat buffers without computing it.
+    fn build_from_buffers(
+        &mut self,
+        nodes_buffer: &[f32],
+        children_buffer: &[u32],
+        ids: Option<&[u32]>,
+    ) -> i32 {
+        let node_count = nodes_buffer.len() / STYLE_STRIDE;
+        if !nodes_buffer.len().is_multiple_of(STYLE_STRIDE) {
+            return -2;
+        }
+        let node_id_at = |i: usize| ids.map_or(i as u32, |ids| ids[i]);
+
+        // Validate before resetting so a rejected buffer leaves the previous tree intact.
+        if let Some(ids) = ids {
+            if ids.len() != node_count {
+                return -45;
+            }
+            let mut seen = HashSet::with_capacity(ids.len());
+            if !ids.iter().all(|id| seen.insert(*id)) {
+                return -46;
+            }
+        }
+        self.invalid_style = None;
+        for (i, style_slice) in nodes_buffer.chunks_exact(STYLE_STRIDE).enumerate() {
+            if !self.check_finite(node_id_at(i), style_slice) {
+                return -28;
+            }
+            // A childless node's offset is never read, so it need not point into the buffer.
+            let children_count = style_slice[StyleProp::ChildrenCount as usize] as usize;
+            if children_count == 0 {
+                continue;
+            }
+            let children_offset = style_slice[StyleProp::ChildrenOffset as usize] as usize;
+            let Some(children_ids) = children_offset
+                .checked_add(children_count)
+                .and_then(|end| children_buffer.get(children_offset..end))
+            else {
+                return -35;
+            };
+            let mut seen = HashSet::with_capacity(children_count);
+            if !children_ids.iter().all(|child_id| seen.insert(*child_id)) {
+                return -50;
+            }
+        }
+
+        self.reset();
+
+        for i in 0..node_count {
+            let node_id = node_id_at(i);
+            let style_slice = &nodes_buffer[i * STYLE_STRIDE..(i + 1) * STYLE_STRIDE];
+            let style = Self::style_from_slice(style_slice);
+
+            let taffy_node = match self.taffy.new_leaf(style) {
+                Ok(taffy_node) => taffy_node,
+                Err(error) => return taffy_error_code(&error),
+            };
+            self.nodes.insert(node_id, taffy_node);
+            self.node_id_map.insert(taffy_node, node_id);
+            self.set_anchor_flag(taffy_node, Self::anchors_to_root(style_slice));
+            self.set_contents_flag(taffy_node, Self::is_contents_style(style_slice));
+            self.record_style_lanes(taffy_node, style_slice);
+        }
+
+        for i in 0..node_count {
+            let node_id = node_id_at(i);
+            let style_slice = &nodes_buffer[i * STYLE_STRIDE..(i + 1) * STYLE_STRIDE];
+            let children_count = style_slice[StyleProp::ChildrenCount as usize] as usize;
+            if children_count > 0 {
+                let children_offset = style_slice[StyleProp::ChildrenOffset as usize] as usize;
+                let children_ids_slice =
+                    &children_buffer[children_offset..children_offset + children_count];
+                self.children_scratch.clear();
+                self.children_scratch.extend(
+                    children_ids_slice
+                        .iter()
+                        .filter_map(|child_id| self.nodes.get(child_id))
+                        .copied(),
+                );
+                if let Some(taffy_node) = self.nodes.get(&node_id).copied()
+                    && let Err(error) = self.wire_children(taffy_node)
+                {
+                    return taffy_error_code(&error);
+                }
+            }
+        }
+
+        0
+    }
+
+    fn compute_results(&mut self, root_node: NodeId, available: Size<AvailableSpace>) -> i32 {
+        self.record_cache_stats(&[root_node]);
+        let compute_start = self.start_timer();
+        if let Err(error) = self.taffy.compute_layout(root_node, available) {
+            return taffy_error_code(&error);
+        }
+        self.last_layout = Some((root_node, available));
+
+        let emit_start = self.start_timer();
+        self.emit_sorted_results();
+        self.record_compute_and_emit(compute_start, emit_start);
+        0
+    }
+
+    /// Lays out every parentless node as its own root at the origin, then emits all nodes.
+    fn compute_forest_results(&mut self) -> i32 {
+        let mut roots: Vec<(u32, NodeId)> = self
+            .nodes
+            .iter()
+            .filter(|(_, taffy_id)| self.taffy.parent(**taffy_id).is_none())
+            .map(|(js_id, taffy_id)| (*js_id, *taffy_id))
+            .collect();
+        roots.sort_unstable_by_key(|(js_id, _)| *js_id);
+        let roots: Vec<NodeId> = roots.into_iter().map(|(_, taffy_id)| taffy_id).collect();
+
+        self.record_cache_stats(&roots);
+        let compute_start = self.start_timer();
+        for root in &roots {
+            if let Err(error) = self.taffy.compute_layout(*root, Size::MAX_CONTENT) {
+                return taffy_error_code(&error);
+            }
+        }
+        // There is no single root to replay after an intrinsic measurement.
+        self.last_layout = None;
+
+        let emit_start = self.start_timer();
+        self.emit_sorted_results();
+        self.record_compute_and_emit(compute_start, emit_start);
+        0
+    }
+
+    /// Returns every `display: none` node and its descendants, following anchored nodes
+    /// through their logical parents.
+    fn hidden_nodes(&self) -> HashSet<NodeId> {
+        let mut hidden = HashSet::new();
+        let mut stack: Vec<NodeId> = self
+            .nodes
+            .values()
+            .copied()
+            .filter(|node| self.is_display_none(*node))
+            .collect();
+        while let Some(node) = stack.pop() {
+            if hidden.insert(node) {
+                stack.extend(self.logical_children(node));
+            }
+        }
+        hidden
+    }
+
+    fn is_display_none(&self, node: NodeId) -> bool {
+        !self.is_contents(node)
+            && self
+                .taffy
+                .style(node)
+                .is_ok_and(|style| style.display == Display::None)
+    }
 
+    /// Fills the results buffer with every node, in ascending js id order.
+    fn emit_sorted_results(&mut self) {
+        let hidden = if self.skip_hidden {
+            self.hidden_nodes()
+        } else {
+            HashSet::new()
+        };
         self.results_buffer.clear();
-        for (taffy_id, js_id) in &self.node_id_map {
+        self.emit_order.clear();
+        self.emit_order.extend(
+            self.nodes
+                .iter()
+                .map(|(js_id, taffy_id)| (*js_id, *taffy_id)),
+        );
+        self.emit_order.sort_unstable_by_key(|(js_id, _)| *js_id);
+        for (js_id, taffy_id) in &self.emit_order {
+            if hidden.contains(taffy_id) {
+                continue;
+            }
             if let Ok(layout) = self.taffy.layout(*taffy_id) {
+                let mut location = if self.anchored.is_empty() {
+                    layout.location
+                } else {
+                    self.logical_location(*taffy_id, layout.location)
+                };
+                if self.zero_size_at_flow
+                    && let Some(flow_location) = self.zero_size_location(*taffy_id, layout)
+                {
+                    location = flow_location;
+                }
+                self.results_buffer.push(*js_id as f32);
+                self.results_buffer.push(location.x);
+                self.results_buffer.push(location.y);
+                self.results_buffer.push(layout.size.width);
+                self.results_buffer.push(layout.size.height);
+            }
+        }
+        self.record_changes();
+    }
+
+    /// Diffs the freshly emitted results against the rects of the previous frame. Ids that
+    /// were emitted last time but not this time count as changed too, since their old region
+    /// is stale, and lose their rect so a later return at the same place is reported.
+    fn record_changes(&mut self) {
+        self.changed_ids.clear();
+        for result in self.results_buffer.chunks_exact(RESULT_STRIDE) {
+            let js_id = result[0] as u32;
+            let rect = [result[1], result[2], result[3], result[4]];
+            if self.last_rects.insert(js_id, rect) != Some(rect) {
+                self.changed_ids.push(js_id);
+            }
+        }
+        // Every emitted id is in `last_rects` now, so any extra entry was dropped.
+        let emitted_count = self.results_buffer.len() / RESULT_STRIDE;
+        if self.last_rects.len() > emitted_count {
+            let emitted: HashSet<u32> = self
+                .results_buffer
+                .chunks_exact(RESULT_STRIDE)
+                .map(|result| result[0] as u32)
+                .collect();
+            let mut dropped: Vec<u32> = self
+                .last_rects
+                .keys()
+                .filter(|js_id| !emitted.contains(js_id))
+                .copied()
+                .collect();
+            dropped.sort_unstable();
+            for js_id in &dropped {
+                self.last_rects.remove(js_id);
+            }
+            self.changed_ids.extend(dropped);
+        }
+    }
+
+    /// Computes `root_node` as an independent root and emits only its subtree, depth-first.
+    fn compute_subtree_results(
+        &mut self,
+        root_node: NodeId,
+        available: Size<AvailableSpace>,
+    ) -> i32 {
+        self.record_cache_stats(&[root_node]);
+        if self.timings_enabled {
+            self.last_timings.build = Duration::ZERO;
+        }
+        let compute_start = self.start_timer();
+        if let Err(error) = self.taffy.compute_layout(root_node, available) {
+            return taffy_error_code(&error);
+        }
+        self.last_layout = Some((root_node, available));
+
+        let emit_start = self.start_timer();
+        let hidden = if self.skip_hidden {
+            self.hidden_nodes()
+        } else {
+            HashSet::new()
+        };
+        self.results_buffer.clear();
+        let mut stack = vec![root_node];
+        while let Some(taffy_id) = stack.pop() {
+            if hidden.contains(&taffy_id) {
+                continue;
+            }
+            if let (Some(js_id), Ok(layout)) =
+                (self.node_id_map.get(&taffy_id), self.taffy.layout(taffy_id))
+            {
+                // The subtree root was laid out on its own, not in its parent's flow.
+                let mut location = layout.location;
+                if self.zero_size_at_flow
+                    && taffy_id != root_node
+                    && let Some(flow_location) = self.zero_size_location(taffy_id, layout)
+                {
+                    location = flow_location;
+                }
                 self.results_buffer.push(*js_id as f32);
-                self.results_buffer.push(layout.location.x);
-                self.results_buffer.push(layout.location.y);
+                self.results_buffer.push(location.x);
+                self.results_buffer.push(location.y);
                 self.results_buffer.push(layout.size.width);
                 self.results_buffer.push(layout.size.height);
             }
+            let child_start = stack.len();
+            stack.extend(self.taffy.child_ids(taffy_id));
+            stack[child_start..].reverse();
+        }
+        self.record_changes();
+        self.record_compute_and_emit(compute_start, emit_start);
+        0
+    }
+
+    /// Lays `node` out as a root under `available` and returns its size, then replays the
+    /// last compute so cached layouts match what the results buffer describes.
+    fn measure_intrinsic(
+        &mut self,
+        node: NodeId,
+        available: Size<AvailableSpace>,
+    ) -> Result<Size<f32>, i32> {
+        let size = self
+            .taffy
+            .compute_layout(node, available)
+            .and_then(|()| self.taffy.layout(node))
+            .map_err(|error| taffy_error_code(&error))?
+            .size;
+
+        // The measurement overwrote this subtree's layouts; without dropping its caches the
+        // replay would hit them and leave the overwritten rects in place.
+        let mut stack = vec![node];
+        while let Some(taffy_id) = stack.pop() {
+            let _ = self.taffy.mark_dirty(taffy_id);
+            stack.extend(self.taffy.child_ids(taffy_id));
+        }
+        // The recorded root may have been removed by a later op batch that failed early.
+        if let Some((root, available)) = self.last_layout {
+            let _ = self.taffy.compute_layout(root, available);
+        }
+
+        Ok(size)
+    }
+
+    /// Renders `node` and its descendants as an indented outline of ids, style highlights
+    /// and computed rects.
+    fn write_tree(&self, node: NodeId, depth: usize, out: &mut String) {
+        let Ok(style) = self.taffy.style(node) else {
+            return;
+        };
+        let layout = self.taffy.layout(node).copied().unwrap_or(Layout::new());
+        let js_id = self.node_id_map.get(&node).copied();
+
+        let _ = write!(out, "{:indent$}", "", indent = depth * 2);
+        match js_id {
+            Some(js_id) => {
+                let _ = write!(out, "#{js_id}");
+            }
+            None => out.push('?'),
+        }
+        let _ = write!(out, " {:?}", style.display);
+        if style.display == Display::Flex {
+            let _ = write!(out, " {:?}", style.flex_direction);
+        }
+        if style.position == Position::Absolute {
+            out.push_str(" Absolute");
+        }
+        if style.flex_grow != 0.0 {
+            let _ = write!(out, " grow={}", style.flex_grow);
+        }
+        let _ = writeln!(
+            out,
+            " [x: {} y: {} w: {} h: {}]",
+            layout.location.x, layout.location.y, layout.size.width, layout.size.height
+        );
+
+        for child in self.taffy.child_ids(node) {
+            self.write_tree(child, depth + 1, out);
+        }
+    }
+}
+
+fn overflow_from_f32(value: f32) -> Overflow {
+    match value as i32 {
+        1 => Overflow::Clip,
+        2 => Overflow::Hidden,
+        3 => Overflow::Scroll,
+        _ => Overflow::Visible,
+    }
+}
+
+/// NaN means "unconstrained" (max-content); any other value is a definite size.
+fn available_space_from_f32(value: f32) -> AvailableSpace {
+    if value.is_nan() {
+        AvailableSpace::MaxContent
+    } else {
+        AvailableSpace::Definite(value)
+    }
+}
+
+/// Maps an `AvailableSpaceMode` that needs no value; `Definite` is rejected.
+fn intrinsic_space_from_u32(mode: u32) -> Option<AvailableSpace> {
+    match mode {
+        x if x == AvailableSpaceMode::MaxContent as u32 => Some(AvailableSpace::MaxContent),
+        x if x == AvailableSpaceMode::MinContent as u32 => Some(AvailableSpace::MinContent),
+        _ => None,
+    }
+}
+
+/// Like `intrinsic_space_from_u32`, plus `Definite` for `value`, which must be finite.
+fn available_space_from_mode(mode: u32, value: f32) -> Option<AvailableSpace> {
+    match mode {
+        x if x == AvailableSpaceMode::Definite as u32 => {
+            value.is_finite().then_some(AvailableSpace::Definite(value))
         }
+        _ => intrinsic_space_from_u32(mode),
+    }
+}
+
+/// 0 leaves the alignment unset (`normal` for items, `auto` for self).
+fn justify_from_f32(value: f32) -> Option<AlignItems> {
+    match value as i32 {
+        1 => Some(AlignItems::Start),
+        2 => Some(AlignItems::End),
+        3 => Some(AlignItems::Center),
+        4 => Some(AlignItems::Stretch),
+        _ => None,
+    }
+}
+
+/// NaN is `auto`: no minimum beyond the content-based one, or no maximum.
+fn dimension_from_f32(value: f32) -> Dimension {
+    if value.is_nan() {
+        Dimension::auto()
+    } else {
+        length(value)
+    }
+}
+
+/// NaN is an `auto` margin, which takes a share of the free space; `auto` on both sides of
+/// an axis centers the node on it.
+fn margin_from_f32(value: f32) -> LengthPercentageAuto {
+    if value.is_nan() {
+        LengthPercentageAuto::auto()
+    } else {
+        length(value)
+    }
+}
+
+/// Unit 1 reads `value` as a percentage of the container (10 for 10%), anything else as
+/// points. An unset (NaN) gap is CSS `normal`, which is 0 for flex containers.
+fn gap_from_lanes(value: f32, unit: f32) -> LengthPercentage {
+    let value = if value.is_nan() { 0.0 } else { value };
+    if unit as i32 == 1 {
+        percent(value / 100.0)
+    } else {
+        length(value)
     }
 }
 
@@ -156,6 +978,25 @@ pub extern "C" fn create_engine() -> *mut LayoutEngineState {
     Box::into_raw(Box::new(LayoutEngineState::new()))
 }
 
+#[unsafe(no_mangle)]
+pub extern "C" fn create_engine_with_capacity(node_hint: usize) -> *mut LayoutEngineState {
+    Box::into_raw(Box::new(LayoutEngineState::with_capacity(node_hint)))
+}
+
+/// Returns an independent deep copy of the engine, tree, id maps and last results included,
+/// e.g. to try candidate layouts without disturbing the live engine. Returns null for a null
+/// engine; the copy is freed with `destroy_engine` like any other.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn clone_engine(
+    engine_ptr: *mut LayoutEngineState,
+) -> *mut LayoutEngineState {
+    if engine_ptr.is_null() {
+        return std::ptr::null_mut();
+    }
+    let engine = unsafe { &*engine_ptr };
+    Box::into_raw(Box::new(engine.clone()))
+}
+
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn destroy_engine(ptr: *mut LayoutEngineState) {
     if ptr.is_null() {
@@ -166,6 +1007,29 @@ pub unsafe extern "C" fn destroy_engine(ptr: *mut LayoutEngineState) {
     }
 }
 
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn reset_engine(ptr: *mut LayoutEngineState) {
+    if ptr.is_null() {
+        return;
+    }
+    let engine = unsafe { &mut *ptr };
+    engine.reset();
+    engine.last_rects.clear();
+    engine.changed_ids.clear();
+    engine.invalid_style = None;
+    engine.cache_stats = CacheStats::default();
+    engine.last_timings = LayoutTimings::default();
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn trim_engine_memory(ptr: *mut LayoutEngineState) {
+    if ptr.is_null() {
+        return;
+    }
+    let engine = unsafe { &mut *ptr };
+    engine.trim_memory();
+}
+
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn compute_layout_from_buffers(
     engine_ptr: *mut LayoutEngineState,
@@ -194,193 +1058,935 @@ pub unsafe extern "C" fn compute_layout_from_buffers(
         unsafe { std::slice::from_raw_parts(children_buffer_ptr, children_buffer_len) }
     };
 
-    let node_count = nodes_buffer_len / STYLE_STRIDE;
-    if nodes_buffer_len % STYLE_STRIDE != 0 {
-        return -2;
-    }
-
-    engine.nodes.clear();
-    engine.node_id_map.clear();
-    engine.taffy.clear();
-
-    for i in 0..node_count {
-        let node_id = i as u32;
-        let style_slice = &nodes_buffer[i * STYLE_STRIDE..(i + 1) * STYLE_STRIDE];
-        let style = LayoutEngineState::style_from_slice(style_slice);
-
-        let taffy_node = engine.taffy.new_leaf(style).unwrap();
-        engine.nodes.insert(node_id, taffy_node);
-        engine.node_id_map.insert(taffy_node, node_id);
-    }
-
-    for i in 0..node_count {
-        let node_id = i as u32;
-        let style_slice = &nodes_buffer[i * STYLE_STRIDE..(i + 1) * STYLE_STRIDE];
-        let children_count = style_slice[StyleProp::ChildrenCount as usize] as usize;
-        if children_count > 0 {
-            let children_offset = style_slice[StyleProp::ChildrenOffset as usize] as usize;
-            let children_ids_slice =
-                &children_buffer[children_offset..children_offset + children_count];
-            let taffy_children: Vec<NodeId> = children_ids_slice
-                .iter()
-                .filter_map(|child_id| engine.nodes.get(child_id))
-                .map(|id| *id)
-                .collect();
-            if let Some(taffy_node) = engine.nodes.get(&node_id) {
-                engine
-                    .taffy
-                    .set_children(*taffy_node, &taffy_children)
-                    .unwrap();
-            }
-        }
-    }
-
-    let Some(root_node) = engine.nodes.get(&0).copied() else {
-        return -3;
-    };
-
-    engine.compute_results(root_node);
-
-    0
+    engine.compute_from_buffers(nodes_buffer, children_buffer, None)
 }
 
+/// Like `compute_layout_from_buffers`, with an explicit js id per node in `ids_ptr` instead
+/// of array positions, so reordering the nodes array between frames keeps each node's id.
+/// Children buffer entries refer to these ids, and the tree is computed from id 0.
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn apply_ops_and_compute(
+pub unsafe extern "C" fn compute_layout_from_buffers_with_ids(
     engine_ptr: *mut LayoutEngineState,
-    ops_ptr: *const u32,
-    ops_len: usize,
-    styles_ptr: *const f32,
-    styles_len: usize,
-    children_ptr: *const u32,
-    children_len: usize,
+    nodes_buffer_ptr: *const f32,
+    nodes_buffer_len: usize,
+    children_buffer_ptr: *const u32,
+    children_buffer_len: usize,
+    ids_ptr: *const u32,
+    ids_len: usize,
 ) -> i32 {
     if engine_ptr.is_null() {
         return -1;
     }
 
     let engine = unsafe { &mut *engine_ptr };
-    let ops: &[u32] = if ops_len == 0 {
+    let nodes_buffer: &[f32] = if nodes_buffer_len == 0 {
         &[]
-    } else if ops_ptr.is_null() {
-        return -6;
+    } else if nodes_buffer_ptr.is_null() {
+        return -4;
     } else {
-        unsafe { std::slice::from_raw_parts(ops_ptr, ops_len) }
+        unsafe { std::slice::from_raw_parts(nodes_buffer_ptr, nodes_buffer_len) }
     };
-    let styles: &[f32] = if styles_len == 0 {
+    let children_buffer: &[u32] = if children_buffer_len == 0 {
         &[]
-    } else if styles_ptr.is_null() {
-        return -7;
+    } else if children_buffer_ptr.is_null() {
+        return -5;
     } else {
-        unsafe { std::slice::from_raw_parts(styles_ptr, styles_len) }
+        unsafe { std::slice::from_raw_parts(children_buffer_ptr, children_buffer_len) }
+    };
+    let ids: &[u32] = if ids_len == 0 {
+        &[]
+    } else if ids_ptr.is_null() {
+        return -44;
+    } else {
+        unsafe { std::slice::from_raw_parts(ids_ptr, ids_len) }
+    };
+
+    engine.compute_from_buffers(nodes_buffer, children_buffer, Some(ids))
+}
+
+/// Like `compute_layout_from_buffers`, but first checks the style stride the caller encoded
+/// with against this library's and returns -37 on a mismatch, so a binding built against an
+/// older ABI fails loudly instead of having its buffers misread.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn compute_layout_from_buffers_checked(
+    engine_ptr: *mut LayoutEngineState,
+    nodes_buffer_ptr: *const f32,
+    nodes_buffer_len: usize,
+    children_buffer_ptr: *const u32,
+    children_buffer_len: usize,
+    caller_stride: u32,
+) -> i32 {
+    if engine_ptr.is_null() {
+        return -1;
+    }
+    if caller_stride as usize != STYLE_STRIDE {
+        return -37;
+    }
+
+    unsafe {
+        compute_layout_from_buffers(
+            engine_ptr,
+            nodes_buffer_ptr,
+            nodes_buffer_len,
+            children_buffer_ptr,
+            children_buffer_len,
+        )
+    }
+}
+
+/// Like `compute_layout_from_buffers`, but for a forest: node 0 is not required, and every
+/// node without a parent is laid out as an independent root at the origin.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn compute_layout_forest_from_buffers(
+    engine_ptr: *mut LayoutEngineState,
+    nodes_buffer_ptr: *const f32,
+    nodes_buffer_len: usize,
+    children_buffer_ptr: *const u32,
+    children_buffer_len: usize,
+) -> i32 {
+    if engine_ptr.is_null() {
+        return -1;
+    }
+
+    let engine = unsafe { &mut *engine_ptr };
+    let nodes_buffer: &[f32] = if nodes_buffer_len == 0 {
+        &[]
+    } else if nodes_buffer_ptr.is_null() {
+        return -4;
+    } else {
+        unsafe { std::slice::from_raw_parts(nodes_buffer_ptr, nodes_buffer_len) }
+    };
+    let children_buffer: &[u32] = if children_buffer_len == 0 {
+        &[]
+    } else if children_buffer_ptr.is_null() {
+        return -5;
+    } else {
+        unsafe { std::slice::from_raw_parts(children_buffer_ptr, children_buffer_len) }
+    };
+
+    let build_start = engine.start_timer();
+    let status = engine.build_from_buffers(nodes_buffer, children_buffer, None);
+    if status != 0 {
+        return status;
+    }
+    engine.record_build(build_start);
+    engine.compute_forest_results()
+}
+
+/// Same as `compute_layout_from_buffers`, then copies the results into caller memory and
+/// returns the number of floats written. Returns -31 if `out_cap` is too small; the required
+/// length is then available from `get_results_len`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn compute_layout_into(
+    engine_ptr: *mut LayoutEngineState,
+    nodes_buffer_ptr: *const f32,
+    nodes_buffer_len: usize,
+    children_buffer_ptr: *const u32,
+    children_buffer_len: usize,
+    out_ptr: *mut f32,
+    out_cap: usize,
+) -> i32 {
+    if out_cap > 0 && out_ptr.is_null() {
+        return -32;
+    }
+
+    let status = unsafe {
+        compute_layout_from_buffers(
+            engine_ptr,
+            nodes_buffer_ptr,
+            nodes_buffer_len,
+            children_buffer_ptr,
+            children_buffer_len,
+        )
+    };
+    if status != 0 {
+        return status;
+    }
+
+    let engine = unsafe { &*engine_ptr };
+    let results = &engine.results_buffer;
+    if results.len() > out_cap {
+        return -31;
+    }
+    if !results.is_empty() {
+        unsafe { std::ptr::copy_nonoverlapping(results.as_ptr(), out_ptr, results.len()) };
+    }
+    results.len() as i32
+}
+
+/// Same as `compute_layout_from_buffers`, then writes the results pointer and length (in
+/// floats) to `out_ptr_out` and `out_len_out`, saving hosts with costly calls the separate
+/// `get_results_ptr`/`get_results_len` crossings. The pointer stays valid until the next call
+/// that changes the engine; the out-params are left untouched on failure.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn compute_layout_and_emit(
+    engine_ptr: *mut LayoutEngineState,
+    nodes_buffer_ptr: *const f32,
+    nodes_buffer_len: usize,
+    children_buffer_ptr: *const u32,
+    children_buffer_len: usize,
+    out_ptr_out: *mut *const f32,
+    out_len_out: *mut usize,
+) -> i32 {
+    if engine_ptr.is_null() {
+        return -1;
+    }
+    if out_ptr_out.is_null() || out_len_out.is_null() {
+        return -47;
+    }
+
+    let status = unsafe {
+        compute_layout_from_buffers(
+            engine_ptr,
+            nodes_buffer_ptr,
+            nodes_buffer_len,
+            children_buffer_ptr,
+            children_buffer_len,
+        )
+    };
+    if status != 0 {
+        return status;
+    }
+
+    let engine = unsafe { &*engine_ptr };
+    unsafe {
+        *out_ptr_out = engine.results_buffer.as_ptr();
+        *out_len_out = engine.results_buffer.len();
+    }
+    0
+}
+
+/// Builds the tree from a UTF-8 JSON description shaped like the TS `LayoutInputNode`
+/// and computes it. Js ids are assigned in depth-first pre-order, starting at 0 for the root.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn compute_layout_from_json(
+    engine_ptr: *mut LayoutEngineState,
+    json_ptr: *const u8,
+    json_len: usize,
+) -> i32 {
+    if engine_ptr.is_null() {
+        return -1;
+    }
+
+    let engine = unsafe { &mut *engine_ptr };
+    let json: &[u8] = if json_len == 0 {
+        &[]
+    } else if json_ptr.is_null() {
+        return -22;
+    } else {
+        unsafe { std::slice::from_raw_parts(json_ptr, json_len) }
+    };
+
+    let Ok(root) = serde_json::from_slice::<json::JsonNode>(json) else {
+        return -23;
+    };
+
+    let mut nodes_buffer = Vec::new();
+    let mut children_buffer = Vec::new();
+    root.encode(&mut nodes_buffer, &mut children_buffer);
+    engine.compute_from_buffers(&nodes_buffer, &children_buffer, None)
+}
+
+/// Applies a batch of incremental ops in order, then computes from node 0.
+///
+/// The batch is all-or-nothing: it is validated before anything is applied, and a rejected
+/// batch leaves the tree and the results buffer as they were. An id removed earlier in the
+/// batch is unknown to later ops unless a `CreateLeaf` recreates it, and a `CreateLeaf` for
+/// an id that is still live is rejected with -51. `RemoveNode` leaves the node's children in
+/// place without a parent; `RemoveSubtree` removes them too.
+///
+/// `SetStylePartial` (`[op, node, offset, count]`) patches only some style lanes of a node:
+/// `styles[offset..offset + 2 * count]` holds `(lane index, value)` pairs, and every other
+/// lane keeps the value the node last had.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn apply_ops_and_compute(
+    engine_ptr: *mut LayoutEngineState,
+    ops_ptr: *const u32,
+    ops_len: usize,
+    styles_ptr: *const f32,
+    styles_len: usize,
+    children_ptr: *const u32,
+    children_len: usize,
+) -> i32 {
+    if engine_ptr.is_null() {
+        return -1;
+    }
+
+    let engine = unsafe { &mut *engine_ptr };
+    let ops: &[u32] = if ops_len == 0 {
+        &[]
+    } else if ops_ptr.is_null() {
+        return -6;
+    } else {
+        unsafe { std::slice::from_raw_parts(ops_ptr, ops_len) }
+    };
+    let styles: &[f32] = if styles_len == 0 {
+        &[]
+    } else if styles_ptr.is_null() {
+        return -7;
+    } else {
+        unsafe { std::slice::from_raw_parts(styles_ptr, styles_len) }
+    };
+    let children: &[u32] = if children_len == 0 {
+        &[]
+    } else if children_ptr.is_null() {
+        return -8;
+    } else {
+        unsafe { std::slice::from_raw_parts(children_ptr, children_len) }
+    };
+
+    engine.apply_ops(ops, styles, children)
+}
+
+/// Like `apply_ops_and_compute`, without the compute: the batch is applied all-or-nothing
+/// and the results buffer keeps the last computed layout. Lets a host build a tree across
+/// several batches, including before node 0 exists, and lay it out once with
+/// `compute_current`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn apply_ops(
+    engine_ptr: *mut LayoutEngineState,
+    ops_ptr: *const u32,
+    ops_len: usize,
+    styles_ptr: *const f32,
+    styles_len: usize,
+    children_ptr: *const u32,
+    children_len: usize,
+) -> i32 {
+    if engine_ptr.is_null() {
+        return -1;
+    }
+
+    let engine = unsafe { &mut *engine_ptr };
+    let ops: &[u32] = if ops_len == 0 {
+        &[]
+    } else if ops_ptr.is_null() {
+        return -6;
+    } else {
+        unsafe { std::slice::from_raw_parts(ops_ptr, ops_len) }
+    };
+    let styles: &[f32] = if styles_len == 0 {
+        &[]
+    } else if styles_ptr.is_null() {
+        return -7;
+    } else {
+        unsafe { std::slice::from_raw_parts(styles_ptr, styles_len) }
+    };
+    let children: &[u32] = if children_len == 0 {
+        &[]
+    } else if children_ptr.is_null() {
+        return -8;
+    } else {
+        unsafe { std::slice::from_raw_parts(children_ptr, children_len) }
+    };
+
+    engine.apply_batch(ops, styles, children, false)
+}
+
+/// Lays out the current tree from `root_js_id` under the given available size (NaN for an
+/// unconstrained axis) and emits every node, as after a full compute. Pairs with
+/// `apply_ops` to compute once after several batches.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn compute_current(
+    engine_ptr: *mut LayoutEngineState,
+    root_js_id: u32,
+    available_width: f32,
+    available_height: f32,
+) -> i32 {
+    if engine_ptr.is_null() {
+        return -1;
+    }
+    if available_width.is_infinite() || available_height.is_infinite() {
+        return -33;
+    }
+
+    let engine = unsafe { &mut *engine_ptr };
+    let Some(root_node) = engine.nodes.get(&root_js_id).copied() else {
+        return -21;
+    };
+    if engine.timings_enabled {
+        engine.last_timings.build = Duration::ZERO;
+    }
+    engine.compute_results(
+        root_node,
+        Size {
+            width: available_space_from_f32(available_width),
+            height: available_space_from_f32(available_height),
+        },
+    )
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn compute_layout_for_root(
+    engine_ptr: *mut LayoutEngineState,
+    root_js_id: u32,
+    available_width: f32,
+    available_height: f32,
+) -> i32 {
+    if engine_ptr.is_null() {
+        return -1;
+    }
+
+    let engine = unsafe { &mut *engine_ptr };
+    let Some(root_node) = engine.nodes.get(&root_js_id).copied() else {
+        return -21;
+    };
+
+    engine.compute_subtree_results(
+        root_node,
+        Size {
+            width: available_space_from_f32(available_width),
+            height: available_space_from_f32(available_height),
+        },
+    )
+}
+
+/// Recomputes the current tree from node 0 under a new available size (NaN for an
+/// unconstrained axis) without rebuilding it, e.g. when only the viewport was resized.
+/// Results cover every node, as after a full compute.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn recompute_with_available(
+    engine_ptr: *mut LayoutEngineState,
+    available_width: f32,
+    available_height: f32,
+) -> i32 {
+    if engine_ptr.is_null() {
+        return -1;
+    }
+    if available_width.is_infinite() || available_height.is_infinite() {
+        return -33;
+    }
+
+    let engine = unsafe { &mut *engine_ptr };
+    if engine.timings_enabled {
+        engine.last_timings.build = Duration::ZERO;
+    }
+    engine.compute_from_root(Size {
+        width: available_space_from_f32(available_width),
+        height: available_space_from_f32(available_height),
+    })
+}
+
+/// Like `compute_layout_for_root`, with an explicit available-space mode per axis:
+/// 0 for max-content, 1 for min-content, 2 for a definite size given by the matching value.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn compute_layout_for_root_with_modes(
+    engine_ptr: *mut LayoutEngineState,
+    root_js_id: u32,
+    width_mode: u32,
+    width: f32,
+    height_mode: u32,
+    height: f32,
+) -> i32 {
+    if engine_ptr.is_null() {
+        return -1;
+    }
+
+    let engine = unsafe { &mut *engine_ptr };
+    let Some(root_node) = engine.nodes.get(&root_js_id).copied() else {
+        return -21;
+    };
+    let (Some(width), Some(height)) = (
+        available_space_from_mode(width_mode, width),
+        available_space_from_mode(height_mode, height),
+    ) else {
+        return -33;
+    };
+
+    engine.compute_subtree_results(root_node, Size { width, height })
+}
+
+/// Measures the intrinsic size of the subtree rooted at `root_js_id` and writes
+/// `[width, height]` to `out_ptr`. Each axis mode is 0 for max-content or 1 for min-content.
+/// The results buffer and the layouts from the last compute are left untouched.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn measure_intrinsic(
+    engine_ptr: *mut LayoutEngineState,
+    root_js_id: u32,
+    width_mode: u32,
+    height_mode: u32,
+    out_ptr: *mut f32,
+) -> i32 {
+    if engine_ptr.is_null() {
+        return -1;
+    }
+    if out_ptr.is_null() {
+        return -24;
+    }
+
+    let engine = unsafe { &mut *engine_ptr };
+    let Some(root_node) = engine.nodes.get(&root_js_id).copied() else {
+        return -21;
+    };
+    let (Some(width), Some(height)) = (
+        intrinsic_space_from_u32(width_mode),
+        intrinsic_space_from_u32(height_mode),
+    ) else {
+        return -25;
+    };
+
+    let size = match engine.measure_intrinsic(root_node, Size { width, height }) {
+        Ok(size) => size,
+        Err(status) => return status,
+    };
+    let out = unsafe { std::slice::from_raw_parts_mut(out_ptr, 2) };
+    out[0] = size.width;
+    out[1] = size.height;
+    0
+}
+
+/// Lays out the subtree rooted at `js_id` under the given available size (NaN for an
+/// unconstrained axis) and writes its `[width, height]` to `out_ptr`, e.g. to find how tall
+/// a label gets at a given max width. Like `measure_intrinsic`, the results buffer and the
+/// layouts from the last compute are left untouched.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn measure_node(
+    engine_ptr: *mut LayoutEngineState,
+    js_id: u32,
+    available_width: f32,
+    available_height: f32,
+    out_ptr: *mut f32,
+) -> i32 {
+    if engine_ptr.is_null() {
+        return -1;
+    }
+    if out_ptr.is_null() {
+        return -24;
+    }
+    if available_width.is_infinite() || available_height.is_infinite() {
+        return -33;
+    }
+
+    let engine = unsafe { &mut *engine_ptr };
+    let Some(node) = engine.nodes.get(&js_id).copied() else {
+        return -21;
+    };
+
+    let available = Size {
+        width: available_space_from_f32(available_width),
+        height: available_space_from_f32(available_height),
+    };
+    let size = match engine.measure_intrinsic(node, available) {
+        Ok(size) => size,
+        Err(status) => return status,
+    };
+    let out = unsafe { std::slice::from_raw_parts_mut(out_ptr, 2) };
+    out[0] = size.width;
+    out[1] = size.height;
+    0
+}
+
+/// Writes a UTF-8 outline of the subtree rooted at `root_js_id` (from the last compute) into
+/// `out_ptr`, truncated to `out_cap` bytes, and returns the full length in bytes. Returns 0
+/// for a null engine or an unknown root.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dump_tree(
+    engine_ptr: *mut LayoutEngineState,
+    root_js_id: u32,
+    out_ptr: *mut u8,
+    out_cap: usize,
+) -> usize {
+    if engine_ptr.is_null() {
+        return 0;
+    }
+
+    let engine = unsafe { &*engine_ptr };
+    let Some(root_node) = engine.nodes.get(&root_js_id).copied() else {
+        return 0;
     };
-    let children: &[u32] = if children_len == 0 {
+
+    let mut dump = String::new();
+    engine.write_tree(root_node, 0, &mut dump);
+
+    if !out_ptr.is_null() {
+        let len = dump.len().min(out_cap);
+        unsafe { std::ptr::copy_nonoverlapping(dump.as_ptr(), out_ptr, len) };
+    }
+    dump.len()
+}
+
+/// Writes a JSON snapshot of the input tree (styles and children by js id) into `out_ptr`,
+/// truncated to `out_cap` bytes, and returns the full length in bytes, or 0 for a null engine.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn export_snapshot(
+    engine_ptr: *mut LayoutEngineState,
+    out_ptr: *mut u8,
+    out_cap: usize,
+) -> usize {
+    if engine_ptr.is_null() {
+        return 0;
+    }
+
+    let engine = unsafe { &*engine_ptr };
+    let json = serde_json::to_string(&engine.export_snapshot()).unwrap();
+
+    if !out_ptr.is_null() {
+        let len = json.len().min(out_cap);
+        unsafe { std::ptr::copy_nonoverlapping(json.as_ptr(), out_ptr, len) };
+    }
+    json.len()
+}
+
+/// Replaces the tree with one read from an `export_snapshot` JSON and computes it from
+/// node 0. On error the current tree is left as it was.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn import_snapshot(
+    engine_ptr: *mut LayoutEngineState,
+    json_ptr: *const u8,
+    json_len: usize,
+) -> i32 {
+    if engine_ptr.is_null() {
+        return -1;
+    }
+
+    let engine = unsafe { &mut *engine_ptr };
+    let json: &[u8] = if json_len == 0 {
         &[]
-    } else if children_ptr.is_null() {
-        return -8;
+    } else if json_ptr.is_null() {
+        return -22;
     } else {
-        unsafe { std::slice::from_raw_parts(children_ptr, children_len) }
+        unsafe { std::slice::from_raw_parts(json_ptr, json_len) }
     };
 
-    let mut i = 0;
-    while i < ops.len() {
-        let opcode = ops[i];
-        i += 1;
+    let Ok(snapshot) = serde_json::from_slice::<snapshot::Snapshot>(json) else {
+        return -23;
+    };
+    engine.import_snapshot(&snapshot)
+}
 
-        match opcode {
-            x if x == OpCode::CreateLeaf as u32 => {
-                if i + 2 > ops.len() {
-                    return -10;
-                }
-                let node_id = ops[i];
-                let style_offset = ops[i + 1] as usize;
-                i += 2;
+/// Writes the js ids whose emitted rect changed in the last compute (including newly added
+/// nodes, then nodes that were emitted before but no longer are) into `out_ptr`, up to
+/// `out_cap` ids, and returns how many there are in total.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn get_changed_node_ids(
+    engine_ptr: *mut LayoutEngineState,
+    out_ptr: *mut u32,
+    out_cap: usize,
+) -> usize {
+    if engine_ptr.is_null() {
+        return 0;
+    }
 
-                if style_offset + STYLE_STRIDE > styles.len() {
-                    return -11;
-                }
+    let engine = unsafe { &*engine_ptr };
+    if !out_ptr.is_null() {
+        let len = engine.changed_ids.len().min(out_cap);
+        unsafe { std::ptr::copy_nonoverlapping(engine.changed_ids.as_ptr(), out_ptr, len) };
+    }
+    engine.changed_ids.len()
+}
 
-                let style = LayoutEngineState::style_from_slice(
-                    &styles[style_offset..style_offset + STYLE_STRIDE],
-                );
+/// Turns per-compute phase timings on (nonzero) or off (0). Timings are off by default and
+/// cost nothing while off.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn set_timings_enabled(engine_ptr: *mut LayoutEngineState, enabled: u32) {
+    if engine_ptr.is_null() {
+        return;
+    }
+    let engine = unsafe { &mut *engine_ptr };
+    engine.timings_enabled = enabled != 0;
+}
 
-                let taffy_node = engine.taffy.new_leaf(style).unwrap();
-                engine.nodes.insert(node_id, taffy_node);
-                engine.node_id_map.insert(taffy_node, node_id);
-            }
-            x if x == OpCode::UpdateStyle as u32 => {
-                if i + 2 > ops.len() {
-                    return -12;
-                }
-                let node_id = ops[i];
-                let style_offset = ops[i + 1] as usize;
-                i += 2;
+/// Leaves `display: none` nodes and all their descendants out of the results from the next
+/// compute on (nonzero), or emits every node (0, the default).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn set_skip_hidden_results(engine_ptr: *mut LayoutEngineState, skip: u32) {
+    if engine_ptr.is_null() {
+        return;
+    }
+    let engine = unsafe { &mut *engine_ptr };
+    engine.skip_hidden = skip != 0;
+}
 
-                if style_offset + STYLE_STRIDE > styles.len() {
-                    return -13;
-                }
+/// Reports in-flow nodes with a zero main-axis size from the next compute on (nonzero) at
+/// the position the flow would give the next item, past their last sized sibling, its margin
+/// and the gap, instead of wherever alignment left them (0, the default). Only flex and block
+/// parents are affected.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn set_zero_size_flow_positions(
+    engine_ptr: *mut LayoutEngineState,
+    enabled: u32,
+) {
+    if engine_ptr.is_null() {
+        return;
+    }
+    let engine = unsafe { &mut *engine_ptr };
+    engine.zero_size_at_flow = enabled != 0;
+}
 
-                let Some(taffy_node) = engine.nodes.get(&node_id).copied() else {
-                    return -14;
-                };
+/// Writes the timings of the last compute to `out_ptr` as four floats: the number of emitted
+/// nodes, then the milliseconds spent building the tree, computing layout and emitting
+/// results. All zeros if nothing was computed while timings were enabled.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn get_last_timings(
+    engine_ptr: *mut LayoutEngineState,
+    out_ptr: *mut f32,
+) -> i32 {
+    if engine_ptr.is_null() {
+        return -1;
+    }
+    if out_ptr.is_null() {
+        return -26;
+    }
 
-                let style = LayoutEngineState::style_from_slice(
-                    &styles[style_offset..style_offset + STYLE_STRIDE],
-                );
-                engine.taffy.set_style(taffy_node, style).unwrap();
-            }
-            x if x == OpCode::SetChildren as u32 => {
-                if i + 3 > ops.len() {
-                    return -15;
-                }
-                let node_id = ops[i];
-                let children_offset = ops[i + 1] as usize;
-                let children_count = ops[i + 2] as usize;
-                i += 3;
+    let engine = unsafe { &*engine_ptr };
+    let timings = engine.last_timings;
+    let out = unsafe { std::slice::from_raw_parts_mut(out_ptr, 4) };
+    out[0] = timings.node_count as f32;
+    out[1] = timings.build.as_secs_f32() * 1000.0;
+    out[2] = timings.compute.as_secs_f32() * 1000.0;
+    out[3] = timings.emit.as_secs_f32() * 1000.0;
+    0
+}
 
-                let Some(taffy_node) = engine.nodes.get(&node_id).copied() else {
-                    return -16;
-                };
+/// Writes `[hits, misses, node_count]` for the last compute to `out_ptr`. A hit is a node
+/// whose cached layout was still valid going in; a miss is one that was dirty.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn get_cache_stats(
+    engine_ptr: *mut LayoutEngineState,
+    out_ptr: *mut u32,
+) -> i32 {
+    if engine_ptr.is_null() {
+        return -1;
+    }
+    if out_ptr.is_null() {
+        return -27;
+    }
 
-                if children_offset + children_count > children.len() {
-                    return -17;
-                }
+    let engine = unsafe { &*engine_ptr };
+    let stats = engine.cache_stats;
+    let out = unsafe { std::slice::from_raw_parts_mut(out_ptr, 3) };
+    out[0] = stats.hits as u32;
+    out[1] = stats.misses as u32;
+    out[2] = (stats.hits + stats.misses) as u32;
+    0
+}
 
-                let mut taffy_children: Vec<NodeId> = Vec::with_capacity(children_count);
-                for child_id in &children[children_offset..children_offset + children_count] {
-                    let Some(child_node) = engine.nodes.get(child_id).copied() else {
-                        return -18;
-                    };
-                    taffy_children.push(child_node);
-                }
-                engine
-                    .taffy
-                    .set_children(taffy_node, &taffy_children)
-                    .unwrap();
-            }
-            x if x == OpCode::RemoveNode as u32 => {
-                if i + 1 > ops.len() {
-                    return -19;
-                }
-                let node_id = ops[i];
-                i += 1;
+/// After a call failed with -28, writes `[js_id, style_lane]` of the infinite value that
+/// was rejected to `out_ptr`. Returns -30 if the last call rejected nothing.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn get_invalid_style(
+    engine_ptr: *mut LayoutEngineState,
+    out_ptr: *mut u32,
+) -> i32 {
+    if engine_ptr.is_null() {
+        return -1;
+    }
+    if out_ptr.is_null() {
+        return -29;
+    }
 
-                if let Some(taffy_node) = engine.nodes.remove(&node_id) {
-                    engine.node_id_map.remove(&taffy_node);
-                    let _ = engine.taffy.remove(taffy_node);
-                }
-            }
-            _ => return -20,
-        }
+    let engine = unsafe { &*engine_ptr };
+    let Some((js_id, prop)) = engine.invalid_style else {
+        return -30;
+    };
+    let out = unsafe { std::slice::from_raw_parts_mut(out_ptr, 2) };
+    out[0] = js_id;
+    out[1] = prop;
+    0
+}
+
+/// Writes the unrounded `[x, y, width, height]` of `node_js_id` from the last compute
+/// into `out_ptr`. The results buffer carries the rounded rects of the same pass.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn get_node_layout_unrounded(
+    engine_ptr: *mut LayoutEngineState,
+    node_js_id: u32,
+    out_ptr: *mut f32,
+) -> i32 {
+    if engine_ptr.is_null() {
+        return -1;
+    }
+    if out_ptr.is_null() {
+        return -34;
+    }
+
+    let engine = unsafe { &*engine_ptr };
+    let Some(node) = engine.nodes.get(&node_js_id).copied() else {
+        return -21;
+    };
+    let layout = engine.taffy.unrounded_layout(node);
+    let location = engine.logical_location_unrounded(node, layout.location);
+    let out = unsafe { std::slice::from_raw_parts_mut(out_ptr, 4) };
+    out.copy_from_slice(&[
+        location.x,
+        location.y,
+        layout.size.width,
+        layout.size.height,
+    ]);
+    0
+}
+
+/// Writes the first baseline of `node_js_id` from the last compute to `out_ptr`, as an offset
+/// from the node's top edge. A node without in-flow children reports its bottom edge.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn get_node_baseline(
+    engine_ptr: *mut LayoutEngineState,
+    node_js_id: u32,
+    out_ptr: *mut f32,
+) -> i32 {
+    if engine_ptr.is_null() {
+        return -1;
+    }
+    if out_ptr.is_null() {
+        return -38;
+    }
+
+    let engine = unsafe { &*engine_ptr };
+    let Some(node) = engine.nodes.get(&node_js_id).copied() else {
+        return -21;
+    };
+    unsafe { *out_ptr = engine.first_baseline(node) };
+    0
+}
+
+/// Writes `[x, y, width, height]` of `node_js_id` from the last compute to `out_ptr`, with the
+/// location in root coordinates instead of relative to the node's parent.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn get_absolute_layout(
+    engine_ptr: *mut LayoutEngineState,
+    node_js_id: u32,
+    out_ptr: *mut f32,
+) -> i32 {
+    if engine_ptr.is_null() {
+        return -1;
+    }
+    if out_ptr.is_null() {
+        return -34;
+    }
+
+    let engine = unsafe { &*engine_ptr };
+    let Some(node) = engine.nodes.get(&node_js_id).copied() else {
+        return -21;
+    };
+    let Ok(layout) = engine.taffy.layout(node) else {
+        return -21;
+    };
+    let location = engine.absolute_location(node, false);
+    let out = unsafe { std::slice::from_raw_parts_mut(out_ptr, 4) };
+    out.copy_from_slice(&[
+        location.x,
+        location.y,
+        layout.size.width,
+        layout.size.height,
+    ]);
+    0
+}
+
+/// Finds the topmost, deepest node of `root_js_id`'s subtree drawn at `(x, y)` in root
+/// coordinates, as of the last compute. Returns 1 and writes its js id to `out_ptr` on a hit,
+/// 0 if no node is there, or a negative status.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hit_test(
+    engine_ptr: *mut LayoutEngineState,
+    root_js_id: u32,
+    x: f32,
+    y: f32,
+    out_ptr: *mut u32,
+) -> i32 {
+    if engine_ptr.is_null() {
+        return -1;
+    }
+    if out_ptr.is_null() {
+        return -49;
     }
 
-    let Some(root_node) = engine.nodes.get(&0).copied() else {
-        return -3;
+    let engine = unsafe { &*engine_ptr };
+    let Some(root_node) = engine.nodes.get(&root_js_id).copied() else {
+        return -21;
+    };
+    let Some(hit) = engine.hit_test(root_node, Point { x, y }) else {
+        return 0;
     };
+    unsafe { *out_ptr = engine.node_id_map[&hit] };
+    1
+}
+
+/// Writes the `[x, y, width, height]` union of the rects of the `ids_len` nodes at `ids_ptr`
+/// from the last compute to `out_ptr`, in root coordinates. An empty set writes an empty box
+/// at the origin.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn get_bounding_box(
+    engine_ptr: *mut LayoutEngineState,
+    ids_ptr: *const u32,
+    ids_len: usize,
+    out_ptr: *mut f32,
+) -> i32 {
+    if engine_ptr.is_null() {
+        return -1;
+    }
+    if out_ptr.is_null() {
+        return -48;
+    }
 
-    engine.compute_results(root_node);
+    let engine = unsafe { &*engine_ptr };
+    let ids: &[u32] = if ids_len == 0 {
+        &[]
+    } else if ids_ptr.is_null() {
+        return -44;
+    } else {
+        unsafe { std::slice::from_raw_parts(ids_ptr, ids_len) }
+    };
+    let mut nodes = Vec::with_capacity(ids.len());
+    for id in ids {
+        let Some(node) = engine.nodes.get(id).copied() else {
+            return -21;
+        };
+        nodes.push(node);
+    }
+    let out = unsafe { std::slice::from_raw_parts_mut(out_ptr, 4) };
+    out.copy_from_slice(&engine.bounding_box(&nodes));
     0
 }
 
+/// Returns how many flex lines the children of `node_js_id` wrapped into in the last compute,
+/// or a negative status. A container that does not wrap always reports 1.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn get_flex_line_count(
+    engine_ptr: *mut LayoutEngineState,
+    node_js_id: u32,
+) -> i32 {
+    if engine_ptr.is_null() {
+        return -1;
+    }
+
+    let engine = unsafe { &*engine_ptr };
+    let Some(node) = engine.nodes.get(&node_js_id).copied() else {
+        return -21;
+    };
+    engine.flex_line_count(node) as i32
+}
+
+/// Returns 1 if `node_js_id` should be drawn and 0 if its `visibility` resolves to hidden,
+/// or a negative status. Hidden nodes keep their box in the results; only drawing is skipped.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn get_node_visibility(
+    engine_ptr: *mut LayoutEngineState,
+    node_js_id: u32,
+) -> i32 {
+    if engine_ptr.is_null() {
+        return -1;
+    }
+
+    let engine = unsafe { &*engine_ptr };
+    let Some(node) = engine.nodes.get(&node_js_id).copied() else {
+        return -21;
+    };
+    engine.is_visible(node) as i32
+}
+
+/// Returns 1 if a node with `node_js_id` currently exists and 0 if not, so a reconciler can
+/// choose between `CreateLeaf` and `UpdateStyle` without probing with an op batch.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn layout_engine_has_node(
+    engine_ptr: *mut LayoutEngineState,
+    node_js_id: u32,
+) -> i32 {
+    if engine_ptr.is_null() {
+        return -1;
+    }
+    let engine = unsafe { &*engine_ptr };
+    engine.nodes.contains_key(&node_js_id) as i32
+}
+
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn get_results_ptr(engine_ptr: *mut LayoutEngineState) -> *const f32 {
     if engine_ptr.is_null() {
@@ -399,6 +2005,54 @@ pub unsafe extern "C" fn get_results_len(engine_ptr: *mut LayoutEngineState) ->
     engine.results_buffer.len()
 }
 
+/// Copies the results of up to `max_nodes` nodes, starting at the `start_node`-th emitted
+/// node, into `out_ptr` and returns how many nodes were written. Fewer are written when
+/// `out_cap` (in floats) or the end of the results comes first, so a host can page through
+/// a large results buffer in fixed-size reads.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn get_results_chunk(
+    engine_ptr: *mut LayoutEngineState,
+    start_node: usize,
+    max_nodes: usize,
+    out_ptr: *mut f32,
+    out_cap: usize,
+) -> usize {
+    if engine_ptr.is_null() || out_ptr.is_null() {
+        return 0;
+    }
+
+    let engine = unsafe { &*engine_ptr };
+    let node_count = engine.results_buffer.len() / RESULT_STRIDE;
+    let count = max_nodes
+        .min(out_cap / RESULT_STRIDE)
+        .min(node_count.saturating_sub(start_node));
+    if count > 0 {
+        let start = start_node * RESULT_STRIDE;
+        let chunk = &engine.results_buffer[start..start + count * RESULT_STRIDE];
+        unsafe { std::ptr::copy_nonoverlapping(chunk.as_ptr(), out_ptr, chunk.len()) };
+    }
+    count
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn get_results_capacity(engine_ptr: *mut LayoutEngineState) -> usize {
+    if engine_ptr.is_null() {
+        return 0;
+    }
+    let engine = unsafe { &*engine_ptr };
+    engine.results_buffer.capacity()
+}
+
+/// Returns a static, NUL-terminated message describing a status code returned by this
+/// library. Codes in -100..=-199 are failures reported by Taffy. The pointer stays valid for
+/// the lifetime of the process and must not be freed.
+#[unsafe(no_mangle)]
+pub extern "C" fn layout_engine_error_string(code: i32) -> *const c_char {
+    errors::error_message(code)
+        .unwrap_or(c"unknown status code")
+        .as_ptr()
+}
+
 // --- FFI boundary introspection (for sync tests) ---
 
 #[unsafe(no_mangle)]
@@ -470,3 +2124,67 @@ pub extern "C" fn layout_engine_style_prop_children_count() -> u32 {
 pub extern "C" fn layout_engine_style_prop_children_offset() -> u32 {
     StyleProp::ChildrenOffset as u32
 }
+
+#[unsafe(no_mangle)]
+pub extern "C" fn layout_engine_opcode_create_leaf() -> u32 {
+    OpCode::CreateLeaf as u32
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn layout_engine_opcode_update_style() -> u32 {
+    OpCode::UpdateStyle as u32
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn layout_engine_opcode_set_children() -> u32 {
+    OpCode::SetChildren as u32
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn layout_engine_opcode_remove_node() -> u32 {
+    OpCode::RemoveNode as u32
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn layout_engine_opcode_remove_subtree() -> u32 {
+    OpCode::RemoveSubtree as u32
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn layout_engine_opcode_set_style_partial() -> u32 {
+    OpCode::SetStylePartial as u32
+}
+
+/// Writes up to `out_len` entries of the ABI info, in this fixed order: ABI version, style
+/// stride, result stride, f32 size, u32 size. Returns the full entry count; entries are
+/// only ever appended, so the version tells bindings which ones to expect.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn layout_engine_abi_info(out_ptr: *mut u32, out_len: usize) -> usize {
+    let info = [
+        layout_engine_abi_version(),
+        layout_engine_style_stride(),
+        layout_engine_result_stride(),
+        layout_engine_f32_size(),
+        layout_engine_u32_size(),
+    ];
+    if !out_ptr.is_null() {
+        let out = unsafe { std::slice::from_raw_parts_mut(out_ptr, out_len) };
+        for (slot, value) in out.iter_mut().zip(info) {
+            *slot = value;
+        }
+    }
+    info.len()
+}
+
+/// Writes up to `out_len` entries of the style offset table (one per `StyleProp`, in
+/// declaration order) and returns the full table length.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn layout_engine_style_layout(out_ptr: *mut u32, out_len: usize) -> usize {
+    if !out_ptr.is_null() {
+        let out = unsafe { std::slice::from_raw_parts_mut(out_ptr, out_len) };
+        for (slot, prop) in out.iter_mut().zip(STYLE_PROP_TABLE) {
+            *slot = prop as u32;
+        }
+    }
+    STYLE_PROP_TABLE.len()
+}
@@ -0,0 +1,79 @@
+//! Flex line counts for wrapping containers.
+//!
+//! Taffy does not keep the lines it broke a container into, so they are recovered from the
+//! computed layouts: items are placed along the main axis in order, and an item that starts
+//! before the previous one ends, past the cross-axis end of the line so far, must have
+//! wrapped onto a new one.
+
+use taffy::prelude::*;
+
+use super::LayoutEngineState;
+
+impl LayoutEngineState {
+    /// Returns how many flex lines `node`'s in-flow children were laid out in by the last
+    /// compute. Anything but a wrapping flex container, and a wrapping one without in-flow
+    /// children, has a single line.
+    pub(crate) fn flex_line_count(&self, node: NodeId) -> usize {
+        let Ok(style) = self.taffy.style(node) else {
+            return 1;
+        };
+        if style.display != Display::Flex || style.flex_wrap == FlexWrap::NoWrap {
+            return 1;
+        }
+
+        let is_row = matches!(
+            style.flex_direction,
+            FlexDirection::Row | FlexDirection::RowReverse
+        );
+        let main_reversed = matches!(
+            style.flex_direction,
+            FlexDirection::RowReverse | FlexDirection::ColumnReverse
+        );
+        let cross_reversed = style.flex_wrap == FlexWrap::WrapReverse;
+
+        // (start, end) of each in-flow item along the main axis, and of its margin box along
+        // the cross axis. Reversed axes advance towards the start, so they are mirrored.
+        let spans = self.taffy.child_ids(node).filter_map(|child| {
+            let child_style = self.taffy.style(child).ok()?;
+            if child_style.position == Position::Absolute || child_style.display == Display::None {
+                return None;
+            }
+            let layout = self.taffy.layout(child).ok()?;
+            let (x, y) = (layout.location.x, layout.location.y);
+            let (width, height) = (layout.size.width, layout.size.height);
+            let margin = layout.margin;
+            let (main, cross) = if is_row {
+                ((x, x + width), (y - margin.top, y + height + margin.bottom))
+            } else {
+                ((y, y + height), (x - margin.left, x + width + margin.right))
+            };
+            let mirror = |(start, end): (f32, f32), reversed: bool| {
+                if reversed {
+                    (-end, -start)
+                } else {
+                    (start, end)
+                }
+            };
+            Some((mirror(main, main_reversed), mirror(cross, cross_reversed)))
+        });
+
+        let mut lines = 1;
+        let mut previous: Option<(f32, f32)> = None;
+        for ((main_start, main_end), (cross_start, cross_end)) in spans {
+            match previous {
+                // Overlapping items (negative margins) start before the previous one ends
+                // too, but they share its line, so only an item that also clears the line on
+                // the cross axis opens a new one.
+                Some((previous_end, line_end))
+                    if main_start < previous_end && cross_start >= line_end =>
+                {
+                    lines += 1;
+                    previous = Some((main_end, cross_end));
+                }
+                Some((_, line_end)) => previous = Some((main_end, line_end.max(cross_end))),
+                None => previous = Some((main_end, cross_end)),
+            }
+        }
+        lines
+    }
+}
@@ -0,0 +1,398 @@
+//! Incremental op batches for `apply_ops_and_compute` and `apply_ops`.
+//!
+//! Ops apply in order, so a batch is checked up front against the ids it would see at each
+//! step: a `RemoveNode` makes its id unknown to every later op until a `CreateLeaf` brings
+//! it back, and a `RemoveSubtree` does the same for a node and everything beneath it. A batch
+//! that fails validation is rejected before anything is mutated, so the host either sees the
+//! whole batch applied or none of it.
+
+use std::collections::{HashMap, HashSet};
+
+use taffy::prelude::*;
+
+use super::errors::taffy_error_code;
+use super::{LayoutEngineState, OpCode, STYLE_STRIDE, StyleProp};
+
+impl LayoutEngineState {
+    /// Walks `ops` without applying them and returns the error code the first bad op
+    /// would produce, including, with `require_root`, a batch that would leave nodes but no
+    /// node 0 to compute from.
+    pub(crate) fn validate_ops(
+        &mut self,
+        ops: &[u32],
+        styles: &[f32],
+        children: &[u32],
+        require_root: bool,
+    ) -> i32 {
+        // Ids created (true) or removed (false) so far in the batch.
+        let mut changed: HashMap<u32, bool> = HashMap::new();
+        // Children set so far in the batch, by parent id; a created node starts with none.
+        let mut changed_children: HashMap<u32, Vec<u32>> = HashMap::new();
+        // Parents set so far in the batch, by child id; `None` for a node created parentless.
+        let mut changed_parent: HashMap<u32, Option<u32>> = HashMap::new();
+
+        let mut i = 0;
+        while i < ops.len() {
+            let opcode = ops[i];
+            i += 1;
+
+            match opcode {
+                x if x == OpCode::CreateLeaf as u32 => {
+                    if i + 2 > ops.len() {
+                        return -10;
+                    }
+                    let node_id = ops[i];
+                    if self.is_live(&changed, node_id) {
+                        return -51;
+                    }
+                    let style_offset = ops[i + 1] as usize;
+                    if style_offset + STYLE_STRIDE > styles.len() {
+                        return -11;
+                    }
+                    i += 2;
+                    let style_slice = &styles[style_offset..style_offset + STYLE_STRIDE];
+                    if !self.check_finite(node_id, style_slice) {
+                        return -28;
+                    }
+                    changed.insert(node_id, true);
+                    changed_children.insert(node_id, Vec::new());
+                    changed_parent.insert(node_id, None);
+                }
+                x if x == OpCode::UpdateStyle as u32 => {
+                    if i + 2 > ops.len() {
+                        return -12;
+                    }
+                    let node_id = ops[i];
+                    let style_offset = ops[i + 1] as usize;
+                    if style_offset + STYLE_STRIDE > styles.len() {
+                        return -13;
+                    }
+                    i += 2;
+                    if !self.is_live(&changed, node_id) {
+                        return -14;
+                    }
+                    let style_slice = &styles[style_offset..style_offset + STYLE_STRIDE];
+                    if !self.check_finite(node_id, style_slice) {
+                        return -28;
+                    }
+                }
+                x if x == OpCode::SetStylePartial as u32 => {
+                    if i + 3 > ops.len() {
+                        return -40;
+                    }
+                    let node_id = ops[i];
+                    let pairs_offset = ops[i + 1] as usize;
+                    let pair_count = ops[i + 2] as usize;
+                    i += 3;
+                    if !self.is_live(&changed, node_id) {
+                        return -42;
+                    }
+                    let Some(pairs) = pair_count
+                        .checked_mul(2)
+                        .and_then(|len| styles.get(pairs_offset..pairs_offset.checked_add(len)?))
+                    else {
+                        return -41;
+                    };
+                    for pair in pairs.chunks_exact(2) {
+                        let prop = pair[0];
+                        if !(prop >= 0.0 && (prop as usize) < STYLE_STRIDE && prop.fract() == 0.0) {
+                            return -43;
+                        }
+                        if pair[1].is_infinite() {
+                            self.invalid_style = Some((node_id, prop as u32));
+                            return -28;
+                        }
+                    }
+                }
+                x if x == OpCode::SetChildren as u32 => {
+                    if i + 3 > ops.len() {
+                        return -15;
+                    }
+                    let node_id = ops[i];
+                    let children_offset = ops[i + 1] as usize;
+                    let children_count = ops[i + 2] as usize;
+                    i += 3;
+                    if !self.is_live(&changed, node_id) {
+                        return -16;
+                    }
+                    if children_offset + children_count > children.len() {
+                        return -17;
+                    }
+                    let child_ids = &children[children_offset..children_offset + children_count];
+                    if !child_ids
+                        .iter()
+                        .all(|child_id| self.is_live(&changed, *child_id))
+                    {
+                        return -18;
+                    }
+                    let mut seen = HashSet::with_capacity(child_ids.len());
+                    if !child_ids.iter().all(|child_id| seen.insert(*child_id)) {
+                        return -50;
+                    }
+                    changed_children.insert(node_id, child_ids.to_vec());
+                    for child_id in child_ids {
+                        changed_parent.insert(*child_id, Some(node_id));
+                    }
+                }
+                x if x == OpCode::RemoveNode as u32 => {
+                    if i + 1 > ops.len() {
+                        return -19;
+                    }
+                    let node_id = ops[i];
+                    i += 1;
+                    changed.insert(node_id, false);
+                }
+                x if x == OpCode::RemoveSubtree as u32 => {
+                    if i + 1 > ops.len() {
+                        return -39;
+                    }
+                    let node_id = ops[i];
+                    i += 1;
+                    let subtree =
+                        self.batch_subtree(&changed, &changed_children, &changed_parent, node_id);
+                    for id in subtree {
+                        changed.insert(id, false);
+                    }
+                }
+                _ => return -20,
+            }
+        }
+
+        if require_root && !self.is_live(&changed, 0) {
+            let live_ids = self
+                .nodes
+                .keys()
+                .chain(changed.keys())
+                .copied()
+                .collect::<HashSet<u32>>()
+                .into_iter()
+                .filter(|id| self.is_live(&changed, *id));
+            return Self::missing_root(live_ids);
+        }
+        0
+    }
+
+    fn is_live(&self, changed: &HashMap<u32, bool>, id: u32) -> bool {
+        changed
+            .get(&id)
+            .copied()
+            .unwrap_or_else(|| self.nodes.contains_key(&id))
+    }
+
+    /// Returns the live ids of `root` and its descendants at this point of the batch, one
+    /// level at a time so a child moved elsewhere earlier in the batch is left out.
+    fn batch_subtree(
+        &self,
+        changed: &HashMap<u32, bool>,
+        changed_children: &HashMap<u32, Vec<u32>>,
+        changed_parent: &HashMap<u32, Option<u32>>,
+        root: u32,
+    ) -> Vec<u32> {
+        let mut subtree = Vec::new();
+        let mut seen = HashSet::new();
+        let mut stack = vec![root];
+        while let Some(id) = stack.pop() {
+            if !self.is_live(changed, id) || !seen.insert(id) {
+                continue;
+            }
+            subtree.push(id);
+            let children = match changed_children.get(&id) {
+                Some(children) => children.clone(),
+                None => self.child_ids_of(id),
+            };
+            stack.extend(children.into_iter().filter(|child| {
+                changed_parent
+                    .get(child)
+                    .is_none_or(|parent| *parent == Some(id))
+            }));
+        }
+        subtree
+    }
+
+    /// Returns the js ids of `id`'s logical children in the current tree.
+    fn child_ids_of(&self, id: u32) -> Vec<u32> {
+        let Some(node) = self.nodes.get(&id).copied() else {
+            return Vec::new();
+        };
+        self.logical_children(node)
+            .into_iter()
+            .filter_map(|child| self.node_id_map.get(&child).copied())
+            .collect()
+    }
+
+    /// Returns the js ids of `root` and its descendants in the current tree, following
+    /// anchored nodes through their logical parents.
+    fn subtree_ids(&self, root: u32) -> Vec<u32> {
+        let mut ids = Vec::new();
+        let mut stack: Vec<NodeId> = self.nodes.get(&root).copied().into_iter().collect();
+        while let Some(node) = stack.pop() {
+            if let Some(js_id) = self.node_id_map.get(&node) {
+                ids.push(*js_id);
+            }
+            stack.extend(self.logical_children(node));
+        }
+        ids
+    }
+
+    /// Replaces the style of an existing node with `style_slice`.
+    fn update_style(&mut self, taffy_node: NodeId, style_slice: &[f32]) -> i32 {
+        let style = Self::style_from_slice(style_slice);
+        if let Err(error) = self.taffy.set_style(taffy_node, style) {
+            return taffy_error_code(&error);
+        }
+        if let Err(error) = self.update_anchor_flag(taffy_node, Self::anchors_to_root(style_slice))
+        {
+            return taffy_error_code(&error);
+        }
+        if let Err(error) =
+            self.update_contents_flag(taffy_node, Self::is_contents_style(style_slice))
+        {
+            return taffy_error_code(&error);
+        }
+        let reorder = self.order_of(taffy_node) != style_slice[StyleProp::Order as usize] as i32;
+        self.record_style_lanes(taffy_node, style_slice);
+        if reorder && let Err(error) = self.reorder_siblings(taffy_node) {
+            return taffy_error_code(&error);
+        }
+        0
+    }
+
+    /// Drops `node_id` from the tree and the id maps. Its children stay, parentless.
+    fn remove_node(&mut self, node_id: u32) -> i32 {
+        let Some(taffy_node) = self.nodes.get(&node_id).copied() else {
+            return 0;
+        };
+        let resync_root = self.forget_anchoring(taffy_node);
+        if let Err(error) = self.forget_contents(taffy_node) {
+            return taffy_error_code(&error);
+        }
+        self.nodes.remove(&node_id);
+        self.node_id_map.remove(&taffy_node);
+        self.style_lanes.remove(&taffy_node);
+        self.host_slots.remove(&taffy_node);
+        let _ = self.taffy.remove(taffy_node);
+        if resync_root && let Err(error) = self.sync_root_children() {
+            return taffy_error_code(&error);
+        }
+        0
+    }
+
+    /// Applies a batch in order and computes from node 0, or leaves everything as it was
+    /// if the batch does not validate.
+    pub(crate) fn apply_ops(&mut self, ops: &[u32], styles: &[f32], children: &[u32]) -> i32 {
+        let status = self.apply_batch(ops, styles, children, true);
+        if status != 0 {
+            return status;
+        }
+        self.compute_from_root(Size::MAX_CONTENT)
+    }
+
+    /// Applies a batch in order without computing, or leaves everything as it was if the
+    /// batch does not validate. Without `require_root` the batch may leave no node 0.
+    pub(crate) fn apply_batch(
+        &mut self,
+        ops: &[u32],
+        styles: &[f32],
+        children: &[u32],
+        require_root: bool,
+    ) -> i32 {
+        let build_start = self.start_timer();
+        self.invalid_style = None;
+        let status = self.validate_ops(ops, styles, children, require_root);
+        if status != 0 {
+            return status;
+        }
+
+        let mut i = 0;
+        while i < ops.len() {
+            let opcode = ops[i];
+            i += 1;
+
+            match opcode {
+                x if x == OpCode::CreateLeaf as u32 => {
+                    let node_id = ops[i];
+                    let style_offset = ops[i + 1] as usize;
+                    i += 2;
+
+                    let style_slice = &styles[style_offset..style_offset + STYLE_STRIDE];
+                    let style = Self::style_from_slice(style_slice);
+
+                    let taffy_node = match self.taffy.new_leaf(style) {
+                        Ok(taffy_node) => taffy_node,
+                        Err(error) => return taffy_error_code(&error),
+                    };
+                    self.nodes.insert(node_id, taffy_node);
+                    self.node_id_map.insert(taffy_node, node_id);
+                    self.set_anchor_flag(taffy_node, Self::anchors_to_root(style_slice));
+                    self.set_contents_flag(taffy_node, Self::is_contents_style(style_slice));
+                    self.record_style_lanes(taffy_node, style_slice);
+                }
+                x if x == OpCode::UpdateStyle as u32 => {
+                    let node_id = ops[i];
+                    let taffy_node = self.nodes[&node_id];
+                    let style_offset = ops[i + 1] as usize;
+                    i += 2;
+
+                    let style_slice = &styles[style_offset..style_offset + STYLE_STRIDE];
+                    let status = self.update_style(taffy_node, style_slice);
+                    if status != 0 {
+                        return status;
+                    }
+                }
+                x if x == OpCode::SetStylePartial as u32 => {
+                    let taffy_node = self.nodes[&ops[i]];
+                    let pairs_offset = ops[i + 1] as usize;
+                    let pair_count = ops[i + 2] as usize;
+                    i += 3;
+
+                    let mut lanes = self.style_lanes[&taffy_node];
+                    for pair in styles[pairs_offset..pairs_offset + pair_count * 2].chunks_exact(2)
+                    {
+                        lanes[pair[0] as usize] = pair[1];
+                    }
+                    let status = self.update_style(taffy_node, &lanes);
+                    if status != 0 {
+                        return status;
+                    }
+                }
+                x if x == OpCode::SetChildren as u32 => {
+                    let taffy_node = self.nodes[&ops[i]];
+                    let children_offset = ops[i + 1] as usize;
+                    let children_count = ops[i + 2] as usize;
+                    i += 3;
+
+                    self.children_scratch.clear();
+                    for child_id in &children[children_offset..children_offset + children_count] {
+                        let child_node = self.nodes[child_id];
+                        self.children_scratch.push(child_node);
+                    }
+                    if let Err(error) = self.wire_children(taffy_node) {
+                        return taffy_error_code(&error);
+                    }
+                }
+                x if x == OpCode::RemoveNode as u32 => {
+                    let node_id = ops[i];
+                    i += 1;
+                    let status = self.remove_node(node_id);
+                    if status != 0 {
+                        return status;
+                    }
+                }
+                x if x == OpCode::RemoveSubtree as u32 => {
+                    let node_id = ops[i];
+                    i += 1;
+                    for id in self.subtree_ids(node_id) {
+                        let status = self.remove_node(id);
+                        if status != 0 {
+                            return status;
+                        }
+                    }
+                }
+                _ => unreachable!("validate_ops rejects unknown opcodes"),
+            }
+        }
+
+        self.record_build(build_start);
+        0
+    }
+}
@@ -0,0 +1,84 @@
+//! CSS `order` for the children of flex and grid containers.
+//!
+//! Taffy lays children out in the order they are wired, so the engine sorts each parent's
+//! children by their `Order` lane before handing them to Taffy, ties keeping the order the
+//! host gave. Each child's slot in the host's list is remembered so a later change to one
+//! child's `order` can re-sort its siblings without the host resending them.
+
+use taffy::TaffyResult;
+use taffy::prelude::*;
+
+use super::{LayoutEngineState, StyleProp};
+
+impl LayoutEngineState {
+    pub(crate) fn order_of(&self, node: NodeId) -> i32 {
+        self.style_lanes
+            .get(&node)
+            .map_or(0, |lanes| lanes[StyleProp::Order as usize] as i32)
+    }
+
+    /// Remembers each child's slot in `children_scratch`, which holds the host's order.
+    pub(crate) fn record_host_slots(&mut self) {
+        for (slot, child) in self.children_scratch.iter().enumerate() {
+            self.host_slots.insert(*child, slot);
+        }
+    }
+
+    /// Stable-sorts `children_scratch` by `order`, then by host slot.
+    pub(crate) fn sort_scratch_by_order(&mut self) {
+        if self
+            .children_scratch
+            .iter()
+            .all(|child| self.order_of(*child) == 0)
+        {
+            return;
+        }
+        let mut scratch = std::mem::take(&mut self.children_scratch);
+        scratch.sort_by_cached_key(|child| (self.order_of(*child), self.host_slots.get(child)));
+        self.children_scratch = scratch;
+    }
+
+    /// Re-sorts the siblings of `node` after its `order` changed.
+    pub(crate) fn reorder_siblings(&mut self, node: NodeId) -> TaffyResult<()> {
+        if self.anchored.contains_key(&node) {
+            return Ok(());
+        }
+        if let Some(contents_node) = self.contents_parent.get(&node).copied() {
+            let mut children = self.contents_children[&contents_node].clone();
+            children.sort_by_cached_key(|child| {
+                (self.order_of(*child), self.host_slots.get(child).copied())
+            });
+            self.contents_children.insert(contents_node, children);
+            return self.respread_contents(contents_node);
+        }
+        let Some(parent) = self.taffy.parent(node) else {
+            return Ok(());
+        };
+
+        if self.nodes.get(&0) == Some(&parent) {
+            self.children_scratch.clear();
+            self.children_scratch
+                .extend_from_slice(&self.root_flow_children);
+            self.children_scratch
+                .sort_by_key(|child| self.host_slots.get(child).copied());
+            self.sort_scratch_by_order();
+            self.root_flow_children.clear();
+            self.root_flow_children
+                .extend_from_slice(&self.children_scratch);
+            return self.sync_root_children();
+        }
+
+        let contents_parent = &self.contents_parent;
+        self.children_scratch.clear();
+        self.children_scratch.extend(
+            self.taffy
+                .child_ids(parent)
+                .filter(|child| !contents_parent.contains_key(child)),
+        );
+        self.children_scratch
+            .sort_by_key(|child| self.host_slots.get(child).copied());
+        self.sort_scratch_by_order();
+        self.expand_contents_in_scratch();
+        self.taffy.set_children(parent, &self.children_scratch)
+    }
+}
@@ -0,0 +1,94 @@
+//! Snapshots of the input tree, for attaching a reproducible layout to a bug report.
+//!
+//! A snapshot lists every node by js id with its raw style lanes and its children as the
+//! host wired them. NaN lanes ("unset") are written as `null`, since JSON has no NaN.
+//! Importing replays the snapshot as one op batch on a fresh tree.
+
+use serde::{Deserialize, Serialize};
+
+use super::{LayoutEngineState, OpCode, STYLE_STRIDE, StyleProp};
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Snapshot {
+    nodes: Vec<SnapshotNode>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SnapshotNode {
+    id: u32,
+    style: Vec<Option<f32>>,
+    children: Vec<u32>,
+}
+
+impl LayoutEngineState {
+    pub(crate) fn export_snapshot(&self) -> Snapshot {
+        let mut ids: Vec<u32> = self.nodes.keys().copied().collect();
+        ids.sort_unstable();
+        let nodes = ids
+            .into_iter()
+            .map(|id| {
+                let node = self.nodes[&id];
+                let mut style: Vec<Option<f32>> = self.style_lanes[&node]
+                    .iter()
+                    .map(|lane| (!lane.is_nan()).then_some(*lane))
+                    .collect();
+                // Children are listed explicitly; the buffer-path lanes would be stale.
+                style[StyleProp::ChildrenCount as usize] = Some(0.0);
+                style[StyleProp::ChildrenOffset as usize] = Some(0.0);
+                let children = self
+                    .logical_children(node)
+                    .iter()
+                    .filter_map(|child| self.node_id_map.get(child).copied())
+                    .collect();
+                SnapshotNode {
+                    id,
+                    style,
+                    children,
+                }
+            })
+            .collect();
+        Snapshot { nodes }
+    }
+
+    /// Rebuilds the tree from `snapshot` and computes it. The current tree is only replaced
+    /// once the snapshot has applied cleanly.
+    pub(crate) fn import_snapshot(&mut self, snapshot: &Snapshot) -> i32 {
+        let mut ops = Vec::new();
+        let mut styles = Vec::with_capacity(snapshot.nodes.len() * STYLE_STRIDE);
+        let mut children = Vec::new();
+        for node in &snapshot.nodes {
+            if node.style.len() != STYLE_STRIDE {
+                return -2;
+            }
+            ops.extend([OpCode::CreateLeaf as u32, node.id, styles.len() as u32]);
+            styles.extend(node.style.iter().map(|lane| lane.unwrap_or(f32::NAN)));
+        }
+        for node in snapshot
+            .nodes
+            .iter()
+            .filter(|node| !node.children.is_empty())
+        {
+            ops.extend([
+                OpCode::SetChildren as u32,
+                node.id,
+                children.len() as u32,
+                node.children.len() as u32,
+            ]);
+            children.extend(&node.children);
+        }
+
+        let mut restored = LayoutEngineState::with_capacity(snapshot.nodes.len());
+        restored.timings_enabled = self.timings_enabled;
+        restored.skip_hidden = self.skip_hidden;
+        restored.zero_size_at_flow = self.zero_size_at_flow;
+        restored.last_rects = std::mem::take(&mut self.last_rects);
+        let status = restored.apply_ops(&ops, &styles, &children);
+        if status == 0 {
+            *self = restored;
+        } else {
+            self.invalid_style = restored.invalid_style;
+            self.last_rects = restored.last_rects;
+        }
+        status
+    }
+}
@@ -0,0 +1,37 @@
+//! CSS `visibility` for renderers.
+//!
+//! Taffy has no notion of visibility and a hidden node keeps its box, so the lane never
+//! reaches the layout: it is only read back from the stored style lanes. Like in CSS, it is
+//! inherited, and a descendant of a hidden node can make itself visible again.
+
+use taffy::prelude::*;
+
+use super::{LayoutEngineState, StyleProp};
+
+impl LayoutEngineState {
+    /// Resolves whether `node` should be drawn: its own `Visibility` lane when set, else its
+    /// logical parent's, up to the root which defaults to visible.
+    pub(crate) fn is_visible(&self, node: NodeId) -> bool {
+        let mut current = Some(node);
+        while let Some(node) = current {
+            let visibility = self
+                .style_lanes
+                .get(&node)
+                .map_or(0, |lanes| lanes[StyleProp::Visibility as usize] as i32);
+            match visibility {
+                1 => return false,
+                2 => return true,
+                _ => {}
+            }
+            current = if let Some(parent) = self.contents_parent.get(&node) {
+                Some(*parent)
+            } else {
+                match self.anchored.get(&node) {
+                    Some((parent, _)) => Some(*parent),
+                    None => self.taffy.parent(node),
+                }
+            };
+        }
+        true
+    }
+}
@@ -0,0 +1,106 @@
+//! Stable positions for zero-sized nodes.
+//!
+//! Taffy places a box without width or height wherever alignment left it, e.g. in the middle
+//! of the free space under `space-between`, which is a poor anchor for a popover attached to
+//! an empty slot. When enabled, such a node is instead reported where the flow would put its
+//! next item: past its last sized in-flow sibling, that sibling's trailing margin and the
+//! container's gap along the main axis, on that sibling's line, or at the parent's content box
+//! origin if there is none. Only nodes that are empty along the main axis count, so a
+//! zero-height divider in a row keeps its place.
+
+use taffy::prelude::*;
+use taffy::{Point, ResolveOrZero};
+
+use super::LayoutEngineState;
+
+/// Whether `layout` takes no room along the main axis of a row (`is_row`) or column.
+fn is_zero_sized(layout: &Layout, is_row: bool) -> bool {
+    if is_row {
+        layout.size.width == 0.0
+    } else {
+        layout.size.height == 0.0
+    }
+}
+
+impl LayoutEngineState {
+    fn is_in_flow(&self, node: NodeId) -> bool {
+        self.taffy.style(node).is_ok_and(|style| {
+            style.position != Position::Absolute && style.display != Display::None
+        })
+    }
+
+    /// Returns the flow position to report for an in-flow, zero-sized child of a flex or
+    /// block container, or `None` to keep Taffy's location.
+    pub(crate) fn zero_size_location(&self, node: NodeId, layout: &Layout) -> Option<Point<f32>> {
+        if !self.is_in_flow(node) {
+            return None;
+        }
+        let parent = self.taffy.parent(node)?;
+        let parent_style = self.taffy.style(parent).ok()?;
+        let parent_layout = self.taffy.layout(parent).ok()?;
+        let (is_row, is_reversed) = match (parent_style.display, parent_style.flex_direction) {
+            (Display::Flex, FlexDirection::Row) => (true, false),
+            (Display::Flex, FlexDirection::RowReverse) => (true, true),
+            (Display::Flex, FlexDirection::Column) | (Display::Block, _) => (false, false),
+            (Display::Flex, FlexDirection::ColumnReverse) => (false, true),
+            _ => return None,
+        };
+        if !is_zero_sized(layout, is_row) {
+            return None;
+        }
+
+        let inset = Rect {
+            left: parent_layout.padding.left + parent_layout.border.left,
+            right: parent_layout.padding.right + parent_layout.border.right,
+            top: parent_layout.padding.top + parent_layout.border.top,
+            bottom: parent_layout.padding.bottom + parent_layout.border.bottom,
+        };
+        let content_size = Size {
+            width: Some(parent_layout.size.width - inset.left - inset.right),
+            height: Some(parent_layout.size.height - inset.top - inset.bottom),
+        };
+        let gap = if parent_style.display == Display::Flex {
+            let gap = parent_style.gap.resolve_or_zero(content_size, |_, _| 0.0);
+            if is_row { gap.width } else { gap.height }
+        } else {
+            0.0
+        };
+        // (main, cross) coordinates of the next free slot.
+        let mut cursor = match (is_row, is_reversed) {
+            (true, false) => (inset.left, inset.top),
+            (true, true) => (parent_layout.size.width - inset.right, inset.top),
+            (false, false) => (inset.top, inset.left),
+            (false, true) => (parent_layout.size.height - inset.bottom, inset.left),
+        };
+        for sibling in self.taffy.child_ids(parent) {
+            if sibling == node {
+                break;
+            }
+            if !self.is_in_flow(sibling) {
+                continue;
+            }
+            let Ok(sibling_layout) = self.taffy.layout(sibling) else {
+                continue;
+            };
+            if is_zero_sized(sibling_layout, is_row) {
+                continue;
+            }
+            let Point { x, y } = sibling_layout.location;
+            let Size { width, height } = sibling_layout.size;
+            let margin = sibling_layout.margin;
+            cursor = match (is_row, is_reversed) {
+                (true, false) => (x + width + margin.right + gap, y),
+                (true, true) => (x - margin.left - gap, y),
+                (false, false) => (y + height + margin.bottom + gap, x),
+                (false, true) => (y - margin.top - gap, x),
+            };
+        }
+
+        let (main, cross) = cursor;
+        Some(if is_row {
+            Point { x: main, y: cross }
+        } else {
+            Point { x: cross, y: main }
+        })
+    }
+}
@@ -5,7 +5,11 @@ export type { Rect };
 export type Dimension = number | string | "auto";
 
 export interface LayoutStyle {
-  display?: "flex" | "none";
+  /**
+   * `contents` generates no box: the node's children are laid out as children of its parent,
+   * its other styles are ignored and it reports an empty rect.
+   */
+  display?: "flex" | "none" | "block" | "grid" | "contents";
   position?: "relative" | "absolute";
 
   width?: Dimension;
@@ -14,16 +18,22 @@ export interface LayoutStyle {
   minHeight?: Dimension;
   maxWidth?: Dimension;
   maxHeight?: Dimension;
+  /** Width divided by height, used to size an axis that is otherwise auto. */
+  aspectRatio?: number;
   layoutBoundary?: boolean;
 
   padding?: number | [number, number, number, number];
-  margin?: number | [number, number, number, number];
+  /** `"auto"` takes a share of the free space; auto on both sides of an axis centers the node. */
+  margin?: MarginValue | [MarginValue, MarginValue, MarginValue, MarginValue];
 
   flexDirection?: "row" | "column" | "row-reverse" | "column-reverse";
   flexWrap?: "nowrap" | "wrap" | "wrap-reverse";
   flexGrow?: number;
   flexShrink?: number;
+  /** Points, a percentage of the container's main size such as `"50%"`, or `"auto"`. */
   flexBasis?: Dimension;
+  /** Lays the node out among its siblings by ascending order; ties keep tree order. */
+  order?: number;
 
   justifyContent?:
     | "flex-start"
@@ -33,11 +43,50 @@ export interface LayoutStyle {
     | "space-around"
     | "space-evenly";
   alignItems?: "flex-start" | "flex-end" | "center" | "baseline" | "stretch";
+  alignContent?:
+    | "flex-start"
+    | "flex-end"
+    | "center"
+    | "space-between"
+    | "space-around"
+    | "space-evenly"
+    | "stretch";
   alignSelf?: "auto" | "flex-start" | "flex-end" | "center" | "baseline" | "stretch";
+  /** Inline-axis alignment of grid items within their cells; ignored outside grid. */
+  justifyItems?: "normal" | "start" | "end" | "center" | "stretch";
+  justifySelf?: "auto" | "start" | "end" | "center" | "stretch";
 
-  gap?: number | { width?: number; height?: number };
+  /**
+   * Points, or a percentage of the container's size along that axis. In the object form,
+   * `width` is the column gap (horizontal, between items on a row) and `height` the row gap.
+   */
+  gap?: GapValue | { width?: GapValue; height?: GapValue };
+
+  overflow?: Overflow;
+  overflowX?: Overflow;
+  overflowY?: Overflow;
+  scrollbarWidth?: number;
+  boxSizing?: "border-box" | "content-box";
+  /** Applies to this node's own row layout; it is not inherited by descendants. */
+  direction?: "ltr" | "rtl";
+  /**
+   * A hidden node keeps its box but should not be drawn. Inherited: descendants are hidden
+   * too unless they set `visible` themselves.
+   */
+  visibility?: "visible" | "hidden";
+  /**
+   * Places an absolutely positioned node against the layout root instead of its parent,
+   * like `position: fixed`. Its reported x/y stay relative to its parent.
+   */
+  anchorRoot?: boolean;
 }
 
+export type MarginValue = number | "auto";
+
+export type GapValue = number | `${number}%`;
+
+export type Overflow = "visible" | "clip" | "hidden" | "scroll";
+
 export interface LayoutInputNode extends LayoutElementShape, LayoutStyle {
   key?: string;
   identifier?: string;
@@ -23,8 +23,21 @@ const layoutStyleKeys = new Set<string>([
   "flexBasis",
   "justifyContent",
   "alignItems",
+  "alignContent",
   "alignSelf",
+  "justifyItems",
+  "justifySelf",
   "gap",
+  "overflow",
+  "overflowX",
+  "overflowY",
+  "scrollbarWidth",
+  "boxSizing",
+  "direction",
+  "anchorRoot",
+  "order",
+  "aspectRatio",
+  "visibility",
   "stack",
 ]);
 
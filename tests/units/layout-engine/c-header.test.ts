@@ -0,0 +1,43 @@
+import { describe, it, expect } from "bun:test";
+import { dlopen, FFIType } from "bun:ffi";
+import path from "node:path";
+import { existsSync, readFileSync } from "node:fs";
+import { resolveDevLibPath } from "./ffi-helpers";
+
+const headerPath = path.join(process.cwd(), "src", "layout-engine", "include", "layout_engine.h");
+
+describe("Layout Engine C header", () => {
+  it("should compile under a C compiler", () => {
+    expect(existsSync(headerPath)).toBe(true);
+
+    // CI installs a compiler, so a missing one is a setup error rather than a reason to pass.
+    const compiler = Bun.which("cc") ?? Bun.which("clang") ?? Bun.which("gcc");
+    if (!compiler) throw new Error("no C compiler (cc, clang or gcc) on PATH");
+
+    const result = Bun.spawnSync([
+      compiler,
+      "-std=c99",
+      "-Wall",
+      "-Wextra",
+      "-Werror",
+      "-fsyntax-only",
+      "-x",
+      "c",
+      headerPath,
+    ]);
+    expect(result.stderr.toString()).toBe("");
+    expect(result.exitCode).toBe(0);
+  });
+
+  it("should declare the same ABI version as the built library", () => {
+    const header = readFileSync(headerPath, "utf8");
+    const match = header.match(/#define LAYOUT_ENGINE_ABI_VERSION (\d+)/);
+    expect(match).not.toBeNull();
+
+    const { symbols } = dlopen(resolveDevLibPath(), {
+      layout_engine_abi_version: { args: [], returns: FFIType.u32 },
+    });
+    expect(Number(match![1])).toBe(symbols.layout_engine_abi_version());
+    expect(header).toContain("struct LayoutEngineState *create_engine(void);");
+  });
+});
@@ -1,19 +1,7 @@
 import { describe, it, expect } from "bun:test";
-import { dlopen, FFIType, suffix } from "bun:ffi";
-import path from "node:path";
+import { dlopen, FFIType, ptr } from "bun:ffi";
 import { existsSync } from "node:fs";
-
-function resolveDevLibPath() {
-  const libName = "liblayout_engine";
-  return path.join(
-    process.cwd(),
-    "src",
-    "layout-engine",
-    "target",
-    "release",
-    `${libName}.${suffix}`,
-  );
-}
+import { resolveDevLibPath, StyleProp } from "./ffi-helpers";
 
 describe("Layout Engine FFI boundary", () => {
   it("should keep Rust and TS buffer layouts in sync", () => {
@@ -38,7 +26,7 @@ describe("Layout Engine FFI boundary", () => {
       layout_engine_style_prop_children_offset: { args: [], returns: FFIType.u32 },
     });
 
-    const expectedAbiVersion = 2;
+    const expectedAbiVersion = 59;
 
     const expectedStylePropIndex = {
       FlexDirection: 2,
@@ -50,7 +38,7 @@ describe("Layout Engine FFI boundary", () => {
       GapColumn: 25,
       ChildrenCount: 26,
       ChildrenOffset: 27,
-      TotalProps: 28,
+      TotalProps: 43,
     } as const;
 
     const expectedResultStride = 5;
@@ -77,4 +65,102 @@ describe("Layout Engine FFI boundary", () => {
       expectedStylePropIndex.ChildrenOffset,
     );
   });
+
+  it("should expose the full style offset table in one call", () => {
+    const { symbols } = dlopen(resolveDevLibPath(), {
+      layout_engine_style_stride: { args: [], returns: FFIType.u32 },
+      layout_engine_style_layout: { args: [FFIType.ptr, FFIType.u64], returns: FFIType.u64 },
+    });
+
+    const stride = symbols.layout_engine_style_stride();
+    expect(Number(symbols.layout_engine_style_layout(null, 0))).toBe(stride);
+
+    const table = new Uint32Array(stride);
+    expect(Number(symbols.layout_engine_style_layout(ptr(table), table.length))).toBe(stride);
+    expect(table[StyleProp.FlexGrow]).toBe(7);
+    expect(table[StyleProp.Width]).toBe(10);
+    expect(table[StyleProp.GapColumn]).toBe(25);
+    expect(table[StyleProp.ChildrenOffset]).toBe(27);
+    expect(Array.from(table)).toEqual(Array.from({ length: stride }, (_, i) => i));
+
+    const partial = new Uint32Array(3).fill(99);
+    expect(Number(symbols.layout_engine_style_layout(ptr(partial), 2))).toBe(stride);
+    expect(Array.from(partial)).toEqual([0, 1, 99]);
+  });
+
+  it("should expose the ABI info in one call", () => {
+    const { symbols } = dlopen(resolveDevLibPath(), {
+      layout_engine_abi_version: { args: [], returns: FFIType.u32 },
+      layout_engine_style_stride: { args: [], returns: FFIType.u32 },
+      layout_engine_result_stride: { args: [], returns: FFIType.u32 },
+      layout_engine_f32_size: { args: [], returns: FFIType.u32 },
+      layout_engine_u32_size: { args: [], returns: FFIType.u32 },
+      layout_engine_abi_info: { args: [FFIType.ptr, FFIType.u64], returns: FFIType.u64 },
+    });
+
+    const length = Number(symbols.layout_engine_abi_info(null, 0));
+    expect(length).toBe(5);
+    const info = new Uint32Array(length);
+    expect(Number(symbols.layout_engine_abi_info(ptr(info), info.length))).toBe(length);
+    expect(Array.from(info)).toEqual([
+      symbols.layout_engine_abi_version(),
+      symbols.layout_engine_style_stride(),
+      symbols.layout_engine_result_stride(),
+      symbols.layout_engine_f32_size(),
+      symbols.layout_engine_u32_size(),
+    ]);
+
+    const partial = new Uint32Array(3).fill(0);
+    expect(Number(symbols.layout_engine_abi_info(ptr(partial), 1))).toBe(length);
+    expect(Array.from(partial)).toEqual([symbols.layout_engine_abi_version(), 0, 0]);
+  });
+
+  it("should describe status codes with static messages", () => {
+    const { symbols } = dlopen(resolveDevLibPath(), {
+      layout_engine_error_string: { args: [FFIType.i32], returns: FFIType.cstring },
+    });
+    const message = (code: number) => String(symbols.layout_engine_error_string(code));
+
+    expect(message(0)).toBe("ok");
+    expect(message(-3)).toBe("unknown status code");
+    expect(message(-21)).toBe("unknown node id");
+    expect(message(-28)).toBe("infinite style value");
+    expect(message(-36)).toBe("no node with id 0 to compute from");
+    // Taffy's own failures get one code each in the reserved range.
+    expect(message(-100)).toBe("taffy: child index out of bounds");
+    expect(message(-101)).toBe("taffy: invalid parent node");
+    expect(message(-102)).toBe("taffy: invalid child node");
+    expect(message(-103)).toBe("taffy: invalid input node");
+    expect(message(-9)).toBe("unknown status code");
+    expect(message(1)).toBe("unknown status code");
+  });
+
+  it("should keep Rust and TS incremental opcodes in sync", () => {
+    const { symbols } = dlopen(resolveDevLibPath(), {
+      layout_engine_opcode_create_leaf: { args: [], returns: FFIType.u32 },
+      layout_engine_opcode_update_style: { args: [], returns: FFIType.u32 },
+      layout_engine_opcode_set_children: { args: [], returns: FFIType.u32 },
+      layout_engine_opcode_remove_node: { args: [], returns: FFIType.u32 },
+      layout_engine_opcode_remove_subtree: { args: [], returns: FFIType.u32 },
+      layout_engine_opcode_set_style_partial: { args: [], returns: FFIType.u32 },
+    });
+
+    const expectedOpCode = {
+      CreateLeaf: 1,
+      UpdateStyle: 2,
+      SetChildren: 3,
+      RemoveNode: 4,
+      RemoveSubtree: 5,
+      SetStylePartial: 6,
+    } as const;
+
+    expect(symbols.layout_engine_opcode_create_leaf()).toBe(expectedOpCode.CreateLeaf);
+    expect(symbols.layout_engine_opcode_update_style()).toBe(expectedOpCode.UpdateStyle);
+    expect(symbols.layout_engine_opcode_set_children()).toBe(expectedOpCode.SetChildren);
+    expect(symbols.layout_engine_opcode_remove_node()).toBe(expectedOpCode.RemoveNode);
+    expect(symbols.layout_engine_opcode_remove_subtree()).toBe(expectedOpCode.RemoveSubtree);
+    expect(symbols.layout_engine_opcode_set_style_partial()).toBe(
+      expectedOpCode.SetStylePartial,
+    );
+  });
 });
@@ -0,0 +1,2109 @@
+import { describe, it, expect, afterAll } from "bun:test";
+import { ptr, toArrayBuffer, type Pointer } from "bun:ffi";
+import {
+  applyOps,
+  AvailableSpaceMode,
+  computeFromNodes,
+  encodeNodes,
+  encodeStyles,
+  LayoutOp,
+  openEngineLib,
+  readRawResults,
+  readResults,
+  RESULT_STRIDE,
+  STYLE_STRIDE,
+  StyleProp,
+  type TestNode,
+} from "./ffi-helpers";
+
+const lib = openEngineLib();
+const engines: Pointer[] = [];
+
+function createEngine(): Pointer {
+  const engine = lib.symbols.create_engine();
+  if (!engine) throw new Error("Failed to create layout engine.");
+  engines.push(engine);
+  return engine;
+}
+
+afterAll(() => {
+  for (const engine of engines) lib.symbols.destroy_engine(engine);
+});
+
+describe("Layout Engine FFI engine lifecycle", () => {
+  it("should reuse a reset engine for a different tree and keep its capacity", () => {
+    const engine = createEngine();
+
+    expect(
+      computeFromNodes(lib, engine, [
+        { style: { Width: 100, Height: 100 }, children: [1, 2] },
+        { style: { Width: 30, Height: 30 } },
+        { style: { Width: 30, Height: 30 } },
+      ]),
+    ).toBe(0);
+    expect(readResults(lib, engine).size).toBe(3);
+    const capacityBefore = lib.symbols.get_results_capacity(engine);
+
+    lib.symbols.reset_engine(engine);
+    expect(Number(lib.symbols.get_results_len(engine))).toBe(0);
+    expect(lib.symbols.get_results_capacity(engine)).toBe(capacityBefore);
+
+    expect(
+      computeFromNodes(lib, engine, [
+        { style: { Width: 40, Height: 20, FlexDirection: 1 }, children: [1] },
+        { style: { Height: 5 } },
+      ]),
+    ).toBe(0);
+    const results = readResults(lib, engine);
+    expect(results.size).toBe(2);
+    expect(results.get(0)).toEqual({ x: 0, y: 0, width: 40, height: 20 });
+    expect(results.get(1)).toEqual({ x: 0, y: 0, width: 40, height: 5 });
+    expect(lib.symbols.get_results_capacity(engine)).toBe(capacityBefore);
+  });
+
+  it("should compute an empty tree to empty results on every path", () => {
+    const engine = createEngine();
+    expect(computeFromNodes(lib, engine, [])).toBe(0);
+    expect(readRawResults(lib, engine)).toEqual(new Float32Array(0));
+    expect(lib.symbols.recompute_with_available(engine, 80, 24)).toBe(0);
+    expect(applyOps(lib, engine, [])).toBe(0);
+
+    expect(computeFromNodes(lib, engine, [{ style: { Width: 10, Height: 10 } }])).toBe(0);
+    expect(readResults(lib, engine).size).toBe(1);
+    expect(computeFromNodes(lib, engine, [])).toBe(0);
+    expect(readRawResults(lib, engine)).toEqual(new Float32Array(0));
+
+    // Removing the last node through an op batch empties the results too.
+    expect(computeFromNodes(lib, engine, [{ style: { Width: 10, Height: 10 } }])).toBe(0);
+    expect(applyOps(lib, engine, [LayoutOp.RemoveNode, 0])).toBe(0);
+    expect(readRawResults(lib, engine)).toEqual(new Float32Array(0));
+    expect(lib.symbols.layout_engine_has_node(engine, 0)).toBe(0);
+  });
+});
+
+describe("Layout Engine FFI capacity hint", () => {
+  it("should size a new engine from the hint and still grow past it", () => {
+    const engine = lib.symbols.create_engine_with_capacity(4);
+    if (!engine) throw new Error("Failed to create layout engine.");
+    engines.push(engine);
+
+    const initialCapacity = Number(lib.symbols.get_results_capacity(engine));
+    expect(initialCapacity).toBeGreaterThanOrEqual(4 * RESULT_STRIDE);
+    expect(initialCapacity).toBeLessThan(15000 * RESULT_STRIDE);
+
+    const leafCount = 50;
+    const tree: TestNode[] = [
+      {
+        style: { Width: 100, FlexDirection: 1 },
+        children: Array.from({ length: leafCount }, (_, i) => i + 1),
+      },
+    ];
+    for (let i = 0; i < leafCount; i++) tree.push({ style: { Height: 1 } });
+    expect(computeFromNodes(lib, engine, tree)).toBe(0);
+
+    const results = readResults(lib, engine);
+    expect(results.size).toBe(leafCount + 1);
+    expect(results.get(leafCount)).toEqual({ x: 0, y: leafCount - 1, width: 100, height: 1 });
+    expect(Number(lib.symbols.get_results_capacity(engine))).toBeGreaterThanOrEqual(
+      (leafCount + 1) * RESULT_STRIDE,
+    );
+  });
+});
+
+describe("Layout Engine FFI engine cloning", () => {
+  it("should fork an engine whose changes leave the original untouched", () => {
+    const engine = createEngine();
+    expect(
+      computeFromNodes(lib, engine, [
+        { style: { Width: 100, Height: 10 }, children: [1, 2] },
+        { style: { Width: 30 } },
+        { style: { Width: 30 } },
+      ]),
+    ).toBe(0);
+    const results = readResults(lib, engine);
+
+    const fork = lib.symbols.clone_engine(engine);
+    if (!fork) throw new Error("Failed to clone layout engine.");
+    engines.push(fork);
+    expect(readResults(lib, fork)).toEqual(results);
+
+    const styles = encodeStyles([{ Width: 50 }]);
+    const ops = [LayoutOp.UpdateStyle, 1, 0, LayoutOp.RemoveNode, 2];
+    expect(applyOps(lib, fork, ops, styles)).toBe(0);
+    const forked = readResults(lib, fork);
+    expect([...forked.keys()]).toEqual([0, 1]);
+    expect(forked.get(1)).toEqual({ x: 0, y: 0, width: 50, height: 10 });
+
+    expect(readResults(lib, engine)).toEqual(results);
+    expect(lib.symbols.recompute_with_available(engine, NaN, NaN)).toBe(0);
+    expect(readResults(lib, engine)).toEqual(results);
+    expect(lib.symbols.layout_engine_has_node(engine, 2)).toBe(1);
+  });
+
+  it("should return null for a null engine", () => {
+    expect(lib.symbols.clone_engine(null)).toBeNull();
+  });
+});
+
+describe("Layout Engine FFI memory trimming", () => {
+  it("should shrink the results buffer after the tree gets much smaller", () => {
+    const engine = createEngine();
+
+    const leafCount = 20000;
+    const bigTree: TestNode[] = [
+      { style: { Width: 100 }, children: Array.from({ length: leafCount }, (_, i) => i + 1) },
+    ];
+    for (let i = 0; i < leafCount; i++) bigTree.push({ style: { Width: 1, Height: 1 } });
+    expect(computeFromNodes(lib, engine, bigTree)).toBe(0);
+    const filledCapacity = Number(lib.symbols.get_results_capacity(engine));
+    expect(filledCapacity).toBeGreaterThanOrEqual((leafCount + 1) * RESULT_STRIDE);
+
+    // Still well utilized: trimming keeps the allocation.
+    lib.symbols.trim_engine_memory(engine);
+    expect(Number(lib.symbols.get_results_capacity(engine))).toBe(filledCapacity);
+
+    expect(
+      computeFromNodes(lib, engine, [
+        { style: { Width: 100, FlexDirection: 1 }, children: [1] },
+        { style: { Height: 1 } },
+      ]),
+    ).toBe(0);
+    lib.symbols.trim_engine_memory(engine);
+
+    const trimmedCapacity = Number(lib.symbols.get_results_capacity(engine));
+    expect(trimmedCapacity).toBeLessThan(filledCapacity);
+    expect(trimmedCapacity).toBeGreaterThanOrEqual(2 * RESULT_STRIDE);
+    expect(readResults(lib, engine).get(1)).toEqual({ x: 0, y: 0, width: 100, height: 1 });
+  });
+});
+
+describe("Layout Engine FFI subtree roots", () => {
+  it("should compute distinct roots from the same populated engine", () => {
+    const engine = createEngine();
+
+    expect(
+      computeFromNodes(lib, engine, [
+        { style: { Width: 100, Height: 100 }, children: [1] },
+        { style: { Width: 10, Height: 10 } },
+        { style: { Width: 50, Height: 20, PaddingLeft: 5 }, children: [3] },
+        { style: { Width: 10, Height: 10 } },
+        { style: { Width: 80 }, children: [5] },
+        { style: { FlexGrow: 1, Height: 10 } },
+      ]),
+    ).toBe(0);
+
+    expect(lib.symbols.compute_layout_for_root(engine, 2, NaN, NaN)).toBe(0);
+    const overlay = readResults(lib, engine);
+    expect([...overlay.keys()]).toEqual([2, 3]);
+    expect(overlay.get(2)).toEqual({ x: 0, y: 0, width: 50, height: 20 });
+    expect(overlay.get(3)).toEqual({ x: 5, y: 0, width: 10, height: 10 });
+
+    expect(lib.symbols.compute_layout_for_root(engine, 4, 200, NaN)).toBe(0);
+    const toolbar = readResults(lib, engine);
+    expect([...toolbar.keys()]).toEqual([4, 5]);
+    expect(toolbar.get(4)).toEqual({ x: 0, y: 0, width: 80, height: 10 });
+    expect(toolbar.get(5)).toEqual({ x: 0, y: 0, width: 80, height: 10 });
+  });
+
+  it("should reject an unknown root id", () => {
+    const engine = createEngine();
+    expect(computeFromNodes(lib, engine, [{ style: { Width: 10, Height: 10 } }])).toBe(0);
+    expect(lib.symbols.compute_layout_for_root(engine, 42, NaN, NaN)).toBe(-21);
+  });
+});
+
+describe("Layout Engine FFI resize", () => {
+  it("should re-resolve the existing tree under new available sizes without a rebuild", () => {
+    const engine = createEngine();
+    // An auto-width wrapping row takes the available width and rewraps its cells.
+    expect(
+      computeFromNodes(lib, engine, [
+        { style: { FlexWrap: 1 }, children: [1, 2, 3] },
+        { style: { Width: 10, Height: 5 } },
+        { style: { Width: 10, Height: 5 } },
+        { style: { Width: 10, Height: 5 } },
+      ]),
+    ).toBe(0);
+    expect(readResults(lib, engine).get(0)).toEqual({ x: 0, y: 0, width: 30, height: 5 });
+
+    const stats = new Uint32Array(3);
+    expect(lib.symbols.recompute_with_available(engine, 25, NaN)).toBe(0);
+    let results = readResults(lib, engine);
+    expect(results.get(0)).toEqual({ x: 0, y: 0, width: 25, height: 10 });
+    expect(results.get(3)).toEqual({ x: 0, y: 5, width: 10, height: 5 });
+    // Nothing was rebuilt, so every node started out clean.
+    expect(lib.symbols.get_cache_stats(engine, ptr(stats))).toBe(0);
+    expect([...stats]).toEqual([4, 0, 4]);
+
+    expect(lib.symbols.recompute_with_available(engine, 15, 30)).toBe(0);
+    results = readResults(lib, engine);
+    expect([...results.keys()]).toEqual([0, 1, 2, 3]);
+    expect(results.get(0)).toEqual({ x: 0, y: 0, width: 15, height: 15 });
+    expect(results.get(2)).toEqual({ x: 0, y: 5, width: 10, height: 5 });
+    expect(results.get(3)).toEqual({ x: 0, y: 10, width: 10, height: 5 });
+  });
+
+  it("should reject infinite sizes", () => {
+    const engine = createEngine();
+    expect(computeFromNodes(lib, engine, [{ style: { Width: 10, Height: 10 } }])).toBe(0);
+    expect(lib.symbols.recompute_with_available(engine, Infinity, NaN)).toBe(-33);
+  });
+});
+
+describe("Layout Engine FFI gap defaults", () => {
+  it("should leave an unset column gap at 0 when only the row gap is set", () => {
+    const engine = createEngine();
+    const item = { Width: 10, Height: 3 };
+    // An unset lane is NaN or 0 depending on the encoder; both must mean no column gap.
+    for (const GapColumn of [NaN, 0]) {
+      expect(
+        computeFromNodes(lib, engine, [
+          { style: { Width: 25, FlexWrap: 1, GapRow: 2, GapColumn }, children: [1, 2, 3] },
+          { style: item },
+          { style: item },
+          { style: item },
+        ]),
+      ).toBe(0);
+      const results = readResults(lib, engine);
+      expect(results.get(2)).toEqual({ x: 10, y: 0, width: 10, height: 3 });
+      expect(results.get(3)).toEqual({ x: 0, y: 5, width: 10, height: 3 });
+    }
+  });
+});
+
+describe("Layout Engine FFI justify-content default", () => {
+  it("should place an inset-less absolute child at flex-start under wrap-reverse", () => {
+    const engine = createEngine();
+    expect(
+      computeFromNodes(lib, engine, [
+        { style: { Width: 100, Height: 10, FlexWrap: 2 }, children: [1, 2] },
+        { style: { PositionType: 1, Width: 10, Height: 5 } },
+        { style: { Width: 20, Height: 5 } },
+      ]),
+    ).toBe(0);
+    // Taffy mirrors flex-start to the main end for such children under wrap-reverse, while
+    // an unset value would fall back to the start edge.
+    const results = readResults(lib, engine);
+    expect(results.get(1)).toEqual({ x: 90, y: 5, width: 10, height: 5 });
+    expect(results.get(2)).toEqual({ x: 0, y: 5, width: 20, height: 5 });
+  });
+});
+
+describe("Layout Engine FFI wrapping toolbar", () => {
+  // Three padded menu items sized by their labels (8, 12 and 6 cells) with auto basis, so each
+  // takes its max-content width and the row wraps where the next item no longer fits. The
+  // expected rects match Taffy's and browsers' flex-wrap output for the same tree.
+  const toolbar: TestNode[] = [
+    { style: { FlexWrap: 1, GapColumn: 1 }, children: [1, 2, 3] },
+    { style: { PaddingLeft: 1, PaddingRight: 1 }, children: [4] },
+    { style: { PaddingLeft: 1, PaddingRight: 1 }, children: [5] },
+    { style: { PaddingLeft: 1, PaddingRight: 1 }, children: [6] },
+    { style: { Width: 8, Height: 1 } },
+    { style: { Width: 12, Height: 1 } },
+    { style: { Width: 6, Height: 1 } },
+  ];
+  const rect = (x: number, y: number, width: number) => ({ x, y, width, height: 1 });
+  const goldens: [number, { width: number; height: number }, ReturnType<typeof rect>[]][] = [
+    [40, { width: 34, height: 1 }, [rect(0, 0, 10), rect(11, 0, 14), rect(26, 0, 8)]],
+    [34, { width: 34, height: 1 }, [rect(0, 0, 10), rect(11, 0, 14), rect(26, 0, 8)]],
+    [30, { width: 30, height: 2 }, [rect(0, 0, 10), rect(11, 0, 14), rect(0, 1, 8)]],
+    [20, { width: 20, height: 3 }, [rect(0, 0, 10), rect(0, 1, 14), rect(0, 2, 8)]],
+    // Items never shrink below their labels, so the widest one overflows a narrower row.
+    [12, { width: 14, height: 3 }, [rect(0, 0, 10), rect(0, 1, 14), rect(0, 2, 8)]],
+  ];
+
+  it("should wrap items at their max-content widths under each available width", () => {
+    const engine = createEngine();
+    expect(computeFromNodes(lib, engine, toolbar)).toBe(0);
+
+    for (const [available, root, items] of goldens) {
+      expect(lib.symbols.recompute_with_available(engine, available, NaN)).toBe(0);
+      const results = readResults(lib, engine);
+      expect(results.get(0)).toEqual({ x: 0, y: 0, ...root });
+      expect([1, 2, 3].map((id) => results.get(id))).toEqual(items);
+    }
+  });
+
+  it("should wrap the middle item at its explicit 20-cell basis", () => {
+    const engine = createEngine();
+    const based = toolbar.map((node, id) =>
+      id === 2 ? { ...node, style: { ...node.style, FlexBasis: 20 } } : node,
+    );
+    expect(computeFromNodes(lib, engine, based)).toBe(0);
+
+    const basisGoldens: typeof goldens = [
+      [40, { width: 40, height: 1 }, [rect(0, 0, 10), rect(11, 0, 20), rect(32, 0, 8)]],
+      [32, { width: 32, height: 2 }, [rect(0, 0, 10), rect(11, 0, 20), rect(0, 1, 8)]],
+      [30, { width: 30, height: 2 }, [rect(0, 0, 10), rect(0, 1, 20), rect(21, 1, 8)]],
+      [20, { width: 20, height: 3 }, [rect(0, 0, 10), rect(0, 1, 20), rect(0, 2, 8)]],
+    ];
+    for (const [available, root, items] of basisGoldens) {
+      expect(lib.symbols.recompute_with_available(engine, available, NaN)).toBe(0);
+      const results = readResults(lib, engine);
+      expect(results.get(0)).toEqual({ x: 0, y: 0, ...root });
+      expect([1, 2, 3].map((id) => results.get(id))).toEqual(items);
+    }
+  });
+});
+
+describe("Layout Engine FFI percentage flex basis", () => {
+  it("should resolve a basis with unit 1 against the container's main size", () => {
+    const engine = createEngine();
+    const half = { FlexBasis: 50, FlexBasisUnit: 1, Height: 1 };
+    expect(
+      computeFromNodes(lib, engine, [
+        { style: { Width: 80, Height: 10, FlexWrap: 1, AlignContent: 1 }, children: [1, 2, 3] },
+        { style: half },
+        { style: { FlexBasis: 25, Height: 1 } },
+        { style: half },
+      ]),
+    ).toBe(0);
+    const results = readResults(lib, engine);
+    expect(results.get(1)).toEqual({ x: 0, y: 0, width: 40, height: 1 });
+    expect(results.get(2)).toEqual({ x: 40, y: 0, width: 25, height: 1 });
+    expect(results.get(3)).toEqual({ x: 0, y: 1, width: 40, height: 1 });
+  });
+});
+
+describe("Layout Engine FFI available-space modes", () => {
+  const { MaxContent: MAX_CONTENT, MinContent: MIN_CONTENT, Definite: DEFINITE } =
+    AvailableSpaceMode;
+
+  // A wrapping row of "words" separated by a column gap; the row gap is left unset.
+  const words: TestNode[] = [
+    { style: { FlexWrap: 1, GapColumn: 10, GapRow: NaN }, children: [1, 2, 3] },
+    { style: { Width: 30, Height: 10 } },
+    { style: { Width: 50, Height: 10 } },
+    { style: { Width: 20, Height: 10 } },
+  ];
+
+  function layoutWith(widthMode: number, width: number) {
+    const engine = createEngine();
+    expect(computeFromNodes(lib, engine, words)).toBe(0);
+    expect(
+      lib.symbols.compute_layout_for_root_with_modes(engine, 0, widthMode, width, MAX_CONTENT, 0),
+    ).toBe(0);
+    return readResults(lib, engine);
+  }
+
+  it("should lay out the same tree at max-content", () => {
+    const results = layoutWith(MAX_CONTENT, 0);
+    expect(results.get(0)).toEqual({ x: 0, y: 0, width: 120, height: 10 });
+    expect(results.get(1)).toEqual({ x: 0, y: 0, width: 30, height: 10 });
+    expect(results.get(2)).toEqual({ x: 40, y: 0, width: 50, height: 10 });
+    expect(results.get(3)).toEqual({ x: 100, y: 0, width: 20, height: 10 });
+  });
+
+  it("should lay out the same tree at min-content", () => {
+    const results = layoutWith(MIN_CONTENT, 0);
+    expect(results.get(0)).toEqual({ x: 0, y: 0, width: 50, height: 30 });
+    expect(results.get(1)).toEqual({ x: 0, y: 0, width: 30, height: 10 });
+    expect(results.get(2)).toEqual({ x: 0, y: 10, width: 50, height: 10 });
+    expect(results.get(3)).toEqual({ x: 0, y: 20, width: 20, height: 10 });
+  });
+
+  it("should lay out the same tree in a definite width", () => {
+    const results = layoutWith(DEFINITE, 85);
+    expect(results.get(0)).toEqual({ x: 0, y: 0, width: 85, height: 20 });
+    expect(results.get(1)).toEqual({ x: 0, y: 0, width: 30, height: 10 });
+    expect(results.get(2)).toEqual({ x: 0, y: 10, width: 50, height: 10 });
+    expect(results.get(3)).toEqual({ x: 60, y: 10, width: 20, height: 10 });
+  });
+
+  it("should cover every combination of width and height modes on one tree", () => {
+    // A wrapping row that reacts to the available width above a wrapping column that only
+    // wraps at min-content height: an auto-height tree keeps its content height under a
+    // definite available height, as under max-content.
+    const engine = createEngine();
+    expect(
+      computeFromNodes(lib, engine, [
+        { style: { FlexDirection: 1, AlignItems: 0 }, children: [1, 2] },
+        { style: { FlexWrap: 1, GapColumn: 10 }, children: [3, 4, 5] },
+        {
+          style: { FlexDirection: 1, FlexWrap: 1, GapColumn: 10, AlignItems: 0 },
+          children: [6, 7],
+        },
+        { style: { Width: 30, Height: 10 } },
+        { style: { Width: 50, Height: 10 } },
+        { style: { Width: 20, Height: 10 } },
+        { style: { Width: 10, Height: 20 } },
+        { style: { Width: 10, Height: 20 } },
+      ]),
+    ).toBe(0);
+
+    const rows: [number, { width: number; height: number }][] = [
+      [MAX_CONTENT, { width: 120, height: 10 }],
+      [MIN_CONTENT, { width: 50, height: 30 }],
+      [DEFINITE, { width: 85, height: 20 }],
+    ];
+    const columns: [number, { width: number; height: number }][] = [
+      [MAX_CONTENT, { width: 10, height: 40 }],
+      [MIN_CONTENT, { width: 30, height: 20 }],
+      [DEFINITE, { width: 10, height: 40 }],
+    ];
+    for (const [widthMode, row] of rows) {
+      for (const [heightMode, column] of columns) {
+        const compute = lib.symbols.compute_layout_for_root_with_modes;
+        expect(compute(engine, 0, widthMode, 85, heightMode, 50)).toBe(0);
+        const results = readResults(lib, engine);
+        expect(results.get(0)).toEqual({
+          x: 0,
+          y: 0,
+          width: row.width,
+          height: row.height + column.height,
+        });
+        expect(results.get(1)).toEqual({ x: 0, y: 0, ...row });
+        expect(results.get(2)).toEqual({ x: 0, y: row.height, ...column });
+      }
+    }
+  });
+
+  it("should reject unknown modes and non-finite definite sizes", () => {
+    const engine = createEngine();
+    expect(computeFromNodes(lib, engine, words)).toBe(0);
+    const compute = lib.symbols.compute_layout_for_root_with_modes;
+    expect(compute(engine, 0, 3, 0, MAX_CONTENT, 0)).toBe(-33);
+    expect(compute(engine, 0, DEFINITE, NaN, MAX_CONTENT, 0)).toBe(-33);
+    expect(compute(engine, 0, MAX_CONTENT, 0, DEFINITE, Infinity)).toBe(-33);
+    expect(compute(engine, 42, MAX_CONTENT, 0, MAX_CONTENT, 0)).toBe(-21);
+  });
+});
+
+describe("Layout Engine FFI floating forest", () => {
+  function computeForest(engine: Pointer, nodes: TestNode[]): number {
+    const encoded = encodeNodes(nodes);
+    return lib.symbols.compute_layout_forest_from_buffers(
+      engine,
+      ptr(encoded.nodes),
+      encoded.nodes.length,
+      ptr(encoded.children),
+      encoded.children.length,
+    );
+  }
+
+  it("should lay out every parentless tree from its own origin", () => {
+    const engine = createEngine();
+    // Three trees on a canvas; node 0 is a child here, so it is not a root.
+    expect(
+      computeForest(engine, [
+        { style: { Width: 5, Height: 5 } },
+        { style: { Width: 40, Height: 10, PaddingLeft: 2 }, children: [0] },
+        { style: { Width: 20, Height: 20 } },
+        { style: { Width: 30, Height: 5 }, children: [4, 5] },
+        { style: { Width: 10 } },
+        { style: { Width: 10 } },
+      ]),
+    ).toBe(0);
+
+    const results = readResults(lib, engine);
+    expect([...results.keys()]).toEqual([0, 1, 2, 3, 4, 5]);
+    expect(results.get(0)).toEqual({ x: 2, y: 0, width: 5, height: 5 });
+    expect(results.get(1)).toEqual({ x: 0, y: 0, width: 40, height: 10 });
+    expect(results.get(2)).toEqual({ x: 0, y: 0, width: 20, height: 20 });
+    expect(results.get(3)).toEqual({ x: 0, y: 0, width: 30, height: 5 });
+    expect(results.get(4)).toEqual({ x: 0, y: 0, width: 10, height: 5 });
+    expect(results.get(5)).toEqual({ x: 10, y: 0, width: 10, height: 5 });
+  });
+});
+
+describe("Layout Engine FFI intrinsic measurement", () => {
+  const { MaxContent: MAX_CONTENT, MinContent: MIN_CONTENT } = AvailableSpaceMode;
+
+  function dump(engine: Pointer): string {
+    const bytes = new Uint8Array(Number(lib.symbols.dump_tree(engine, 0, null, 0)));
+    lib.symbols.dump_tree(engine, 0, ptr(bytes), bytes.length);
+    return new TextDecoder().decode(bytes);
+  }
+
+  it("should measure a wrapping row at min-content and max-content", () => {
+    const engine = createEngine();
+    // A popover whose "words" wrap onto separate lines when squeezed.
+    expect(
+      computeFromNodes(lib, engine, [
+        { style: { Width: 200, Height: 100, FlexDirection: 1 }, children: [1] },
+        { style: { FlexWrap: 1 }, children: [2, 3, 4] },
+        { style: { Width: 30, Height: 10 } },
+        { style: { Width: 50, Height: 10 } },
+        { style: { Width: 20, Height: 10 } },
+      ]),
+    ).toBe(0);
+    const results = readResults(lib, engine);
+    const outline = dump(engine);
+
+    const size = new Float32Array(2);
+    expect(lib.symbols.measure_intrinsic(engine, 1, MIN_CONTENT, MAX_CONTENT, ptr(size))).toBe(0);
+    expect([...size]).toEqual([50, 30]);
+    expect(lib.symbols.measure_intrinsic(engine, 1, MAX_CONTENT, MAX_CONTENT, ptr(size))).toBe(0);
+    expect([...size]).toEqual([100, 10]);
+
+    // The real layout is untouched, both in the results buffer and in the cached rects.
+    expect(readResults(lib, engine)).toEqual(results);
+    expect(dump(engine)).toBe(outline);
+    expect(results.get(1)).toEqual({ x: 0, y: 0, width: 200, height: 10 });
+  });
+
+  it("should reject unknown roots, unknown modes and a null output", () => {
+    const engine = createEngine();
+    expect(computeFromNodes(lib, engine, [{ style: { Width: 10, Height: 10 } }])).toBe(0);
+    const size = new Float32Array(2);
+    expect(lib.symbols.measure_intrinsic(engine, 42, 0, 0, ptr(size))).toBe(-21);
+    expect(lib.symbols.measure_intrinsic(engine, 0, 2, 0, ptr(size))).toBe(-25);
+    expect(lib.symbols.measure_intrinsic(engine, 0, 0, 0, null)).toBe(-24);
+  });
+
+  it("should report a wrapping subtree growing taller as its available width shrinks", () => {
+    const engine = createEngine();
+    expect(
+      computeFromNodes(lib, engine, [
+        { style: { Width: 200, Height: 100, FlexDirection: 1 }, children: [1] },
+        { style: { FlexWrap: 1 }, children: [2, 3, 4] },
+        { style: { Width: 30, Height: 10 } },
+        { style: { Width: 50, Height: 10 } },
+        { style: { Width: 20, Height: 10 } },
+      ]),
+    ).toBe(0);
+    const results = readResults(lib, engine);
+    const outline = dump(engine);
+
+    const size = new Float32Array(2);
+    const sizes = [120, 80, 50].map((width) => {
+      expect(lib.symbols.measure_node(engine, 1, width, NaN, ptr(size))).toBe(0);
+      return [...size];
+    });
+    expect(sizes).toEqual([
+      [100, 10],
+      [80, 20],
+      [50, 30],
+    ]);
+    expect(lib.symbols.measure_node(engine, 1, NaN, NaN, ptr(size))).toBe(0);
+    expect([...size]).toEqual([100, 10]);
+
+    expect(readResults(lib, engine)).toEqual(results);
+    expect(dump(engine)).toBe(outline);
+
+    expect(lib.symbols.measure_node(engine, 42, 10, NaN, ptr(size))).toBe(-21);
+    expect(lib.symbols.measure_node(engine, 1, Infinity, NaN, ptr(size))).toBe(-33);
+    expect(lib.symbols.measure_node(engine, 1, 10, NaN, null)).toBe(-24);
+  });
+});
+
+describe("Layout Engine FFI hidden results", () => {
+  const tree: TestNode[] = [
+    { style: { Width: 100, Height: 10 }, children: [1, 2, 4] },
+    { style: { Width: 10 } },
+    { style: { Display: 1 }, children: [3] },
+    { style: { Width: 10 } },
+    { style: { Width: 10 } },
+  ];
+
+  it("should emit hidden subtrees unless asked to skip them", () => {
+    const engine = createEngine();
+    expect(computeFromNodes(lib, engine, tree)).toBe(0);
+    expect([...readResults(lib, engine).keys()]).toEqual([0, 1, 2, 3, 4]);
+
+    lib.symbols.set_skip_hidden_results(engine, 1);
+    expect(computeFromNodes(lib, engine, tree)).toBe(0);
+    // The visible child of the hidden node is left out along with it.
+    const results = readResults(lib, engine);
+    expect([...results.keys()]).toEqual([0, 1, 4]);
+    expect(results.get(4)).toEqual({ x: 10, y: 0, width: 10, height: 10 });
+
+    expect(lib.symbols.compute_layout_for_root(engine, 0, NaN, NaN)).toBe(0);
+    expect([...readResults(lib, engine).keys()]).toEqual([0, 1, 4]);
+
+    lib.symbols.set_skip_hidden_results(engine, 0);
+    expect(computeFromNodes(lib, engine, tree)).toBe(0);
+    expect([...readResults(lib, engine).keys()]).toEqual([0, 1, 2, 3, 4]);
+  });
+
+  it("should skip a root-anchored descendant of a hidden node on every path", () => {
+    const engine = createEngine();
+    lib.symbols.set_skip_hidden_results(engine, 1);
+    expect(
+      computeFromNodes(lib, engine, [
+        { style: { Width: 100, Height: 10 }, children: [1] },
+        { style: { Display: 1 }, children: [2] },
+        { style: { PositionType: 1, AnchorRoot: 1, Width: 10, Height: 5 } },
+      ]),
+    ).toBe(0);
+    expect([...readResults(lib, engine).keys()]).toEqual([0]);
+
+    // Taffy holds the anchored node under the root, outside its hidden parent.
+    expect(lib.symbols.compute_layout_for_root(engine, 0, NaN, NaN)).toBe(0);
+    expect([...readResults(lib, engine).keys()]).toEqual([0]);
+  });
+});
+
+describe("Layout Engine FFI zero-size positions", () => {
+  // An empty slot between two buttons pushed apart by space-between, centered on the cross
+  // axis.
+  const tree: TestNode[] = [
+    { style: { Width: 100, Height: 10, JustifyContent: 3, AlignItems: 2 }, children: [1, 2, 3] },
+    { style: { Width: 10, Height: 10 } },
+    {},
+    { style: { Width: 10, Height: 10 } },
+  ];
+
+  it("should report an empty slot right after its previous sibling when enabled", () => {
+    const engine = createEngine();
+    expect(computeFromNodes(lib, engine, tree)).toBe(0);
+    // Taffy leaves it in the middle of the free space and the middle of the line.
+    expect(readResults(lib, engine).get(2)).toEqual({ x: 50, y: 5, width: 0, height: 0 });
+
+    lib.symbols.set_zero_size_flow_positions(engine, 1);
+    expect(computeFromNodes(lib, engine, tree)).toBe(0);
+    const results = readResults(lib, engine);
+    expect(results.get(2)).toEqual({ x: 10, y: 0, width: 0, height: 0 });
+    expect(results.get(3)).toEqual({ x: 90, y: 0, width: 10, height: 10 });
+
+    expect(lib.symbols.compute_layout_for_root(engine, 0, NaN, NaN)).toBe(0);
+    expect(readResults(lib, engine).get(2)).toEqual({ x: 10, y: 0, width: 0, height: 0 });
+  });
+
+  it("should start from the parent's content box when no sized sibling comes first", () => {
+    const engine = createEngine();
+    lib.symbols.set_zero_size_flow_positions(engine, 1);
+    expect(
+      computeFromNodes(lib, engine, [
+        { style: { Width: 100, Height: 10, JustifyContent: 2, PaddingLeft: 2 }, children: [1, 2] },
+        {},
+        { style: { Width: 10, Height: 10 } },
+      ]),
+    ).toBe(0);
+    expect(readResults(lib, engine).get(1)).toEqual({ x: 2, y: 0, width: 0, height: 10 });
+  });
+
+  it("should step past the gap and margin, and leave main-axis-sized dividers alone", () => {
+    const engine = createEngine();
+    lib.symbols.set_zero_size_flow_positions(engine, 1);
+    expect(
+      computeFromNodes(lib, engine, [
+        {
+          style: { Width: 100, Height: 10, JustifyContent: 3, GapColumn: 4 },
+          children: [1, 2, 3, 4],
+        },
+        { style: { Width: 10, Height: 10, MarginRight: 3 } },
+        {},
+        { style: { Width: 10, Height: 0 } },
+        { style: { Width: 10, Height: 10 } },
+      ]),
+    ).toBe(0);
+    const results = readResults(lib, engine);
+    expect(results.get(2)).toEqual({ x: 17, y: 0, width: 0, height: 10 });
+    // A zero-height divider in a row still takes room, so Taffy's place is kept.
+    expect(results.get(3)).toEqual({ x: 58, y: 0, width: 10, height: 0 });
+  });
+});
+
+describe("Layout Engine FFI visibility", () => {
+  it("should keep hidden boxes in the layout and report them as not visible", () => {
+    const engine = createEngine();
+    expect(
+      computeFromNodes(lib, engine, [
+        { style: { Width: 20, FlexDirection: 1 }, children: [1, 2] },
+        { style: { Visibility: 1, FlexDirection: 1 }, children: [3, 4] },
+        { style: { Height: 2 } },
+        { style: { Height: 1 } },
+        { style: { Height: 1, Visibility: 2 } },
+      ]),
+    ).toBe(0);
+
+    // The hidden panel still pushes its sibling down.
+    const results = readResults(lib, engine);
+    expect(results.get(1)).toEqual({ x: 0, y: 0, width: 20, height: 2 });
+    expect(results.get(2)).toEqual({ x: 0, y: 2, width: 20, height: 2 });
+
+    const visibility = [0, 1, 2, 3, 4].map((id) => lib.symbols.get_node_visibility(engine, id));
+    // Node 3 inherits hidden, node 4 opts back in.
+    expect(visibility).toEqual([1, 0, 1, 0, 1]);
+    expect(lib.symbols.get_node_visibility(engine, 9)).toBe(-21);
+  });
+});
+
+describe("Layout Engine FFI changed nodes", () => {
+  function changedIds(engine: Pointer): number[] {
+    const out = new Uint32Array(Number(lib.symbols.get_changed_node_ids(engine, null, 0)));
+    lib.symbols.get_changed_node_ids(engine, ptr(out), out.length);
+    return [...out];
+  }
+
+  it("should report only the nodes whose rect changed since the last compute", () => {
+    const engine = createEngine();
+    const tree: TestNode[] = [
+      { style: { Width: 100, Height: 20, FlexDirection: 1 }, children: [1, 2] },
+      { style: { Width: 10, Height: 5 } },
+      { style: { Width: 10, Height: 5 }, children: [3] },
+      { style: { Width: 4, Height: 2 } },
+    ];
+    expect(computeFromNodes(lib, engine, tree)).toBe(0);
+    expect(changedIds(engine)).toEqual([0, 1, 2, 3]);
+
+    expect(computeFromNodes(lib, engine, tree)).toBe(0);
+    expect(changedIds(engine)).toEqual([]);
+
+    // Shifting node 2 moves its child too, but the child's rect is parent-relative.
+    tree[2] = { style: { Width: 10, Height: 5, MarginTop: 3 }, children: [3] };
+    expect(computeFromNodes(lib, engine, tree)).toBe(0);
+    expect(changedIds(engine)).toEqual([2]);
+  });
+
+  it("should report a node that leaves the results, and again when it returns", () => {
+    const engine = createEngine();
+    lib.symbols.set_skip_hidden_results(engine, 1);
+    const tree = (display: number): TestNode[] => [
+      { style: { Width: 100, Height: 20 }, children: [1, 2] },
+      { style: { Width: 10, Height: 5 } },
+      { style: { Width: 10, Height: 5, Display: display } },
+    ];
+    expect(computeFromNodes(lib, engine, tree(0))).toBe(0);
+
+    expect(computeFromNodes(lib, engine, tree(1))).toBe(0);
+    expect(changedIds(engine)).toEqual([2]);
+    // Back at the rect it had before it was hidden.
+    expect(computeFromNodes(lib, engine, tree(0))).toBe(0);
+    expect(changedIds(engine)).toEqual([2]);
+
+    const removed: TestNode[] = [{ ...tree(0)[0], children: [1] }, tree(0)[1]];
+    expect(computeFromNodes(lib, engine, removed)).toBe(0);
+    expect(changedIds(engine)).toEqual([2]);
+  });
+
+  it("should forget previous rects and cache stats on reset_engine", () => {
+    const engine = createEngine();
+    const tree: TestNode[] = [
+      { style: { Width: 100, Height: 20 }, children: [1] },
+      { style: { Width: 10, Height: 5 } },
+    ];
+    expect(computeFromNodes(lib, engine, tree)).toBe(0);
+
+    lib.symbols.reset_engine(engine);
+    expect(changedIds(engine)).toEqual([]);
+    const stats = new Uint32Array(3);
+    expect(lib.symbols.get_cache_stats(engine, ptr(stats))).toBe(0);
+    expect([...stats]).toEqual([0, 0, 0]);
+
+    expect(computeFromNodes(lib, engine, tree)).toBe(0);
+    expect(changedIds(engine)).toEqual([0, 1]);
+  });
+});
+
+describe("Layout Engine FFI timings", () => {
+  function lastTimings(engine: Pointer) {
+    const out = new Float32Array(4);
+    expect(lib.symbols.get_last_timings(engine, ptr(out))).toBe(0);
+    const [nodeCount, buildMs, computeMs, emitMs] = out;
+    return { nodeCount, buildMs, computeMs, emitMs };
+  }
+
+  it("should record phase timings only while enabled", () => {
+    const engine = createEngine();
+    const leafCount = 2000;
+    const tree: TestNode[] = [
+      {
+        style: { Width: 100, FlexDirection: 1 },
+        children: Array.from({ length: leafCount }, (_, i) => i + 1),
+      },
+    ];
+    for (let i = 0; i < leafCount; i++) tree.push({ style: { Height: 1 } });
+
+    expect(computeFromNodes(lib, engine, tree)).toBe(0);
+    expect(lastTimings(engine)).toEqual({ nodeCount: 0, buildMs: 0, computeMs: 0, emitMs: 0 });
+
+    lib.symbols.set_timings_enabled(engine, 1);
+    expect(computeFromNodes(lib, engine, tree)).toBe(0);
+    const timings = lastTimings(engine);
+    expect(timings.nodeCount).toBe(leafCount + 1);
+    expect(timings.buildMs).toBeGreaterThan(0);
+    expect(timings.computeMs).toBeGreaterThan(0);
+    expect(timings.emitMs).toBeGreaterThan(0);
+
+    // Disabling keeps the last recorded values instead of timing further computes.
+    lib.symbols.set_timings_enabled(engine, 0);
+    expect(computeFromNodes(lib, engine, tree.slice(0, 1))).toBe(0);
+    expect(lastTimings(engine)).toEqual(timings);
+  });
+
+  it("should reject a null output", () => {
+    const engine = createEngine();
+    expect(lib.symbols.get_last_timings(engine, null)).toBe(-26);
+  });
+});
+
+describe("Layout Engine FFI cache stats", () => {
+  function cacheStats(engine: Pointer) {
+    const out = new Uint32Array(3);
+    expect(lib.symbols.get_cache_stats(engine, ptr(out))).toBe(0);
+    const [hits, misses, nodeCount] = out;
+    return { hits, misses, nodeCount };
+  }
+
+  it("should report more hits when an unchanged tree is laid out again", () => {
+    const engine = createEngine();
+    const styles = encodeStyles([{ Width: 100, Height: 10 }, { Width: 5, Height: 5 }]);
+    const ops = [
+      LayoutOp.CreateLeaf,
+      0,
+      0,
+      ...[1, 2, 3].flatMap((id) => [LayoutOp.CreateLeaf, id, STYLE_STRIDE]),
+      LayoutOp.SetChildren,
+      0,
+      0,
+      3,
+    ];
+    expect(applyOps(lib, engine, ops, styles, [1, 2, 3])).toBe(0);
+    const first = cacheStats(engine);
+    expect(first).toEqual({ hits: 0, misses: 4, nodeCount: 4 });
+
+    expect(applyOps(lib, engine, [])).toBe(0);
+    const second = cacheStats(engine);
+    expect(second.hits).toBeGreaterThan(first.hits);
+    expect(second).toEqual({ hits: 4, misses: 0, nodeCount: 4 });
+
+    // Restyling a leaf invalidates it and its ancestors only.
+    expect(applyOps(lib, engine, [LayoutOp.UpdateStyle, 2, 0], styles)).toBe(0);
+    expect(cacheStats(engine)).toEqual({ hits: 2, misses: 2, nodeCount: 4 });
+  });
+
+  it("should reject a null output", () => {
+    const engine = createEngine();
+    expect(lib.symbols.get_cache_stats(engine, null)).toBe(-27);
+  });
+});
+
+describe("Layout Engine FFI non-finite input", () => {
+  function invalidStyle(engine: Pointer): number[] | number {
+    const out = new Uint32Array(2);
+    const status = lib.symbols.get_invalid_style(engine, ptr(out));
+    return status === 0 ? [...out] : status;
+  }
+
+  it("should reject an infinite width and keep the previous layout", () => {
+    const engine = createEngine();
+    const tree: TestNode[] = [
+      { style: { Width: 100, Height: 10 }, children: [1] },
+      { style: { Width: 20, Height: 10 } },
+    ];
+    expect(computeFromNodes(lib, engine, tree)).toBe(0);
+    expect(invalidStyle(engine)).toBe(-30);
+    const results = readResults(lib, engine);
+
+    tree[1] = { style: { Width: Infinity, Height: 10 } };
+    expect(computeFromNodes(lib, engine, tree)).toBe(-28);
+    expect(invalidStyle(engine)).toEqual([1, StyleProp.Width]);
+    expect(readResults(lib, engine)).toEqual(results);
+
+    lib.symbols.reset_engine(engine);
+    expect(invalidStyle(engine)).toBe(-30);
+  });
+
+  it("should accept negative values, which are finite", () => {
+    const engine = createEngine();
+    const tree: TestNode[] = [
+      { style: { Width: 50, FlexDirection: 1 }, children: [1, 2] },
+      { style: { Height: 30 } },
+      { style: { Height: 10, MarginTop: -20 } },
+    ];
+    expect(computeFromNodes(lib, engine, tree)).toBe(0);
+    expect(invalidStyle(engine)).toBe(-30);
+    expect(readResults(lib, engine).get(2)).toEqual({ x: 0, y: 10, width: 50, height: 10 });
+
+    const styles = encodeStyles([{ MarginLeft: -5, Height: 10, MarginTop: -20 }]);
+    expect(applyOps(lib, engine, [LayoutOp.UpdateStyle, 2, 0], styles)).toBe(0);
+    expect(readResults(lib, engine).get(2)).toEqual({ x: -5, y: 10, width: 55, height: 10 });
+  });
+
+  it("should reject an infinite style in an op batch", () => {
+    const engine = createEngine();
+    const styles = encodeStyles([{ Width: 100, Height: 10 }, { MarginTop: -Infinity }]);
+    expect(applyOps(lib, engine, [LayoutOp.CreateLeaf, 0, 0], styles)).toBe(0);
+    expect(applyOps(lib, engine, [LayoutOp.UpdateStyle, 0, STYLE_STRIDE], styles)).toBe(-28);
+    expect(invalidStyle(engine)).toEqual([0, StyleProp.MarginTop]);
+    expect(readResults(lib, engine).get(0)).toEqual({ x: 0, y: 0, width: 100, height: 10 });
+  });
+});
+
+describe("Layout Engine FFI children ranges", () => {
+  it("should reject a children range past the end of the children buffer", () => {
+    const engine = createEngine();
+    const tree: TestNode[] = [
+      { style: { Width: 100, Height: 10 }, children: [1] },
+      { style: { Width: 20, Height: 10 } },
+    ];
+    expect(computeFromNodes(lib, engine, tree)).toBe(0);
+    const results = readRawResults(lib, engine);
+
+    const compute = (lane: StyleProp, value: number, node = 0) => {
+      const encoded = encodeNodes(tree);
+      encoded.nodes[node * STYLE_STRIDE + lane] = value;
+      return lib.symbols.compute_layout_from_buffers(
+        engine,
+        ptr(encoded.nodes),
+        encoded.nodes.length,
+        ptr(encoded.children),
+        encoded.children.length,
+      );
+    };
+    expect(compute(StyleProp.ChildrenCount, 5)).toBe(-35);
+    expect(compute(StyleProp.ChildrenOffset, 1e30)).toBe(-35);
+    expect(readRawResults(lib, engine)).toEqual(results);
+
+    // A leaf's offset is never read, wherever it points.
+    expect(compute(StyleProp.ChildrenOffset, 1e30, 1)).toBe(0);
+    expect(readRawResults(lib, engine)).toEqual(results);
+  });
+});
+
+describe("Layout Engine FFI stride check", () => {
+  it("should reject buffers encoded with a different style stride", () => {
+    const engine = createEngine();
+    const tree: TestNode[] = [
+      { style: { Width: 100, Height: 10 }, children: [1] },
+      { style: { Width: 20, Height: 10 } },
+    ];
+    const encoded = encodeNodes(tree);
+    const compute = (callerStride: number) =>
+      lib.symbols.compute_layout_from_buffers_checked(
+        engine,
+        ptr(encoded.nodes),
+        encoded.nodes.length,
+        ptr(encoded.children),
+        encoded.children.length,
+        callerStride,
+      );
+
+    expect(compute(STYLE_STRIDE)).toBe(0);
+    const results = readRawResults(lib, engine);
+    // A binding built against an older ABI, with one lane fewer.
+    expect(compute(STYLE_STRIDE - 1)).toBe(-37);
+    expect(readRawResults(lib, engine)).toEqual(results);
+    expect(lib.symbols.compute_layout_from_buffers_checked(null, null, 0, null, 0, 0)).toBe(-1);
+  });
+});
+
+describe("Layout Engine FFI stable ids", () => {
+  function computeWithIds(engine: Pointer, nodes: TestNode[], ids: number[]): number {
+    const encoded = encodeNodes(nodes);
+    const idsBuffer = new Uint32Array(ids);
+    return lib.symbols.compute_layout_from_buffers_with_ids(
+      engine,
+      encoded.nodes.length > 0 ? ptr(encoded.nodes) : null,
+      encoded.nodes.length,
+      encoded.children.length > 0 ? ptr(encoded.children) : null,
+      encoded.children.length,
+      idsBuffer.length > 0 ? ptr(idsBuffer) : null,
+      idsBuffer.length,
+    );
+  }
+
+  // Children refer to the explicit ids, not to array positions.
+  const root: TestNode = { style: { Width: 30, Height: 5 }, children: [7, 3] };
+  const narrow: TestNode = { style: { Width: 10, Height: 5 } };
+  const wide: TestNode = { style: { Width: 20, Height: 5 } };
+
+  it("should keep each node's id when the nodes array is reordered", () => {
+    const engine = createEngine();
+    expect(computeWithIds(engine, [root, narrow, wide], [0, 7, 3])).toBe(0);
+    const results = readResults(lib, engine);
+    expect([...results.keys()]).toEqual([0, 3, 7]);
+    expect(results.get(7)).toEqual({ x: 0, y: 0, width: 10, height: 5 });
+    expect(results.get(3)).toEqual({ x: 10, y: 0, width: 20, height: 5 });
+
+    expect(computeWithIds(engine, [wide, root, narrow], [3, 0, 7])).toBe(0);
+    expect(readResults(lib, engine)).toEqual(results);
+  });
+
+  it("should reject ids that do not match the nodes one to one", () => {
+    const engine = createEngine();
+    expect(computeWithIds(engine, [root, narrow, wide], [0, 7])).toBe(-45);
+    expect(computeWithIds(engine, [root, narrow, wide], [0, 7, 7])).toBe(-46);
+    expect(computeWithIds(engine, [narrow, wide], [7, 3])).toBe(-36);
+
+    const encoded = encodeNodes([narrow]);
+    expect(
+      lib.symbols.compute_layout_from_buffers_with_ids(
+        engine,
+        ptr(encoded.nodes),
+        encoded.nodes.length,
+        null,
+        0,
+        null,
+        1,
+      ),
+    ).toBe(-44);
+  });
+});
+
+describe("Layout Engine FFI root anchoring", () => {
+  it("should move a node between its parent and the root when its anchor flag changes", () => {
+    const engine = createEngine();
+    const overlay = { PositionType: 1, Width: 30, Height: 5, MarginLeft: 1 };
+    const styles = encodeStyles([
+      { Width: 100, Height: 100, FlexDirection: 1, PaddingLeft: 2, PaddingTop: 2 },
+      { Width: 50, Height: 50, MarginLeft: 10, MarginTop: 20 },
+      { Width: 5, Height: 5 },
+      { ...overlay, AnchorRoot: 1 },
+      { Width: 7, Height: 5 },
+      overlay,
+    ]);
+    const ops = [
+      ...[0, 1, 2, 3, 4].flatMap((id) => [LayoutOp.CreateLeaf, id, id * STYLE_STRIDE]),
+      LayoutOp.SetChildren,
+      1,
+      0,
+      3,
+      LayoutOp.SetChildren,
+      0,
+      3,
+      1,
+    ];
+    expect(applyOps(lib, engine, ops, styles, [2, 3, 4, 1])).toBe(0);
+    expect(readResults(lib, engine).get(3)).toEqual({ x: -9, y: -20, width: 30, height: 5 });
+
+    expect(applyOps(lib, engine, [LayoutOp.UpdateStyle, 3, 5 * STYLE_STRIDE], styles)).toBe(0);
+    let results = readResults(lib, engine);
+    expect(results.get(3)).toEqual({ x: 1, y: 0, width: 30, height: 5 });
+    // Back in its original slot between its siblings.
+    expect(results.get(4)).toEqual({ x: 5, y: 0, width: 7, height: 5 });
+
+    expect(applyOps(lib, engine, [LayoutOp.UpdateStyle, 3, 3 * STYLE_STRIDE], styles)).toBe(0);
+    results = readResults(lib, engine);
+    expect(results.get(3)).toEqual({ x: -9, y: -20, width: 30, height: 5 });
+    expect(results.get(4)).toEqual({ x: 5, y: 0, width: 7, height: 5 });
+  });
+});
+
+describe("Layout Engine FFI caller-owned results", () => {
+  function computeInto(engine: Pointer, nodes: TestNode[], out: Float32Array): number {
+    const encoded = encodeNodes(nodes);
+    return lib.symbols.compute_layout_into(
+      engine,
+      ptr(encoded.nodes),
+      encoded.nodes.length,
+      ptr(encoded.children),
+      encoded.children.length,
+      out.length > 0 ? ptr(out) : null,
+      out.length,
+    );
+  }
+
+  const tree: TestNode[] = [
+    { style: { Width: 100, Height: 10 }, children: [1] },
+    { style: { Width: 20, Height: 10 } },
+  ];
+
+  it("should report the required length when the buffer is too small", () => {
+    const engine = createEngine();
+    expect(computeInto(engine, tree, new Float32Array(RESULT_STRIDE))).toBe(-31);
+    expect(Number(lib.symbols.get_results_len(engine))).toBe(2 * RESULT_STRIDE);
+    expect(computeInto(engine, tree, new Float32Array(0))).toBe(-31);
+  });
+
+  it("should fill an exactly sized buffer", () => {
+    const engine = createEngine();
+    const out = new Float32Array(2 * RESULT_STRIDE);
+    expect(computeInto(engine, tree, out)).toBe(out.length);
+    expect([...out]).toEqual([0, 0, 0, 100, 10, 1, 0, 0, 20, 10]);
+    expect(out).toEqual(readRawResults(lib, engine));
+  });
+});
+
+describe("Layout Engine FFI one-shot compute and emit", () => {
+  const tree: TestNode[] = [
+    { style: { Width: 100, Height: 10 }, children: [1] },
+    { style: { Width: 20, Height: 10 } },
+  ];
+
+  it("should hand back the results pointer and length from the compute call", () => {
+    const engine = createEngine();
+    const encoded = encodeNodes(tree);
+    const resultsPtr = new BigUint64Array(1);
+    const resultsLen = new BigUint64Array(1);
+    expect(
+      lib.symbols.compute_layout_and_emit(
+        engine,
+        ptr(encoded.nodes),
+        encoded.nodes.length,
+        ptr(encoded.children),
+        encoded.children.length,
+        ptr(resultsPtr),
+        ptr(resultsLen),
+      ),
+    ).toBe(0);
+
+    const len = Number(resultsLen[0]);
+    expect(len).toBe(2 * RESULT_STRIDE);
+    const results = new Float32Array(
+      toArrayBuffer(Number(resultsPtr[0]) as Pointer, 0, len * Float32Array.BYTES_PER_ELEMENT),
+    );
+    expect([...results]).toEqual([0, 0, 0, 100, 10, 1, 0, 0, 20, 10]);
+    expect(results).toEqual(readRawResults(lib, engine));
+  });
+
+  it("should reject null out-params and leave them untouched on failure", () => {
+    const engine = createEngine();
+    const encoded = encodeNodes(tree);
+    const resultsLen = new BigUint64Array([7n]);
+    const emit = (nodes: Float32Array, outPtr: Pointer | null) =>
+      lib.symbols.compute_layout_and_emit(
+        engine,
+        ptr(nodes),
+        nodes.length,
+        ptr(encoded.children),
+        encoded.children.length,
+        outPtr,
+        ptr(resultsLen),
+      );
+
+    expect(emit(encoded.nodes, null)).toBe(-47);
+    const resultsPtr = new BigUint64Array(1);
+    expect(emit(encoded.nodes.subarray(1), ptr(resultsPtr))).toBe(-2);
+    expect(resultsPtr[0]).toBe(0n);
+    expect(resultsLen[0]).toBe(7n);
+  });
+});
+
+describe("Layout Engine FFI chunked results", () => {
+  function readChunk(engine: Pointer, start: number, max: number, out: Float32Array, cap?: number) {
+    return Number(lib.symbols.get_results_chunk(engine, start, max, ptr(out), cap ?? out.length));
+  }
+
+  it("should page through the results and reassemble the full buffer", () => {
+    const engine = createEngine();
+    const leaves = Array.from({ length: 10 }, (_, i) => i + 1);
+    const tree: TestNode[] = [
+      { style: { FlexDirection: 1 }, children: leaves },
+      ...leaves.map((i) => ({ style: { Width: i, Height: 1 } })),
+    ];
+    expect(computeFromNodes(lib, engine, tree)).toBe(0);
+    const expected = readRawResults(lib, engine);
+
+    const chunk = new Float32Array(4 * RESULT_STRIDE);
+    const pages: number[] = [];
+    const counts: number[] = [];
+    for (let start = 0; ; ) {
+      const count = readChunk(engine, start, 4, chunk);
+      if (count === 0) break;
+      counts.push(count);
+      pages.push(...chunk.subarray(0, count * RESULT_STRIDE));
+      start += count;
+    }
+    expect(counts).toEqual([4, 4, 3]);
+    expect(new Float32Array(pages)).toEqual(expected);
+  });
+
+  it("should stop at whichever of max_nodes and out_cap is smaller", () => {
+    const engine = createEngine();
+    const tree: TestNode[] = [
+      { children: [1, 2] },
+      { style: { Width: 1 } },
+      { style: { Width: 2 } },
+    ];
+    expect(computeFromNodes(lib, engine, tree)).toBe(0);
+
+    const out = new Float32Array(3 * RESULT_STRIDE);
+    expect(readChunk(engine, 1, 1, out)).toBe(1);
+    expect(out[0]).toBe(1);
+    // Room for two and a bit nodes only ever yields whole nodes.
+    expect(readChunk(engine, 0, 3, out, 2 * RESULT_STRIDE + 4)).toBe(2);
+    expect(readChunk(engine, 3, 3, out)).toBe(0);
+    expect(Number(lib.symbols.get_results_chunk(engine, 0, 3, null, 0))).toBe(0);
+  });
+});
+
+describe("Layout Engine FFI unrounded layout", () => {
+  it("should return exact rects next to the rounded results of the same pass", () => {
+    const engine = createEngine();
+    // Three equal thirds of a 100-cell row.
+    expect(
+      computeFromNodes(lib, engine, [
+        { style: { Width: 100, Height: 10 }, children: [1, 2, 3] },
+        { style: { FlexGrow: 1 } },
+        { style: { FlexGrow: 1 } },
+        { style: { FlexGrow: 1 } },
+      ]),
+    ).toBe(0);
+
+    const rounded = readResults(lib, engine);
+    expect(rounded.get(1)).toEqual({ x: 0, y: 0, width: 33, height: 10 });
+    expect(rounded.get(2)).toEqual({ x: 33, y: 0, width: 34, height: 10 });
+    expect(rounded.get(3)).toEqual({ x: 67, y: 0, width: 33, height: 10 });
+
+    const rect = new Float32Array(4);
+    expect(lib.symbols.get_node_layout_unrounded(engine, 2, ptr(rect))).toBe(0);
+    expect(rect[0]).toBeCloseTo(100 / 3, 4);
+    expect(rect[1]).toBe(0);
+    expect(rect[2]).toBeCloseTo(100 / 3, 4);
+    expect(rect[3]).toBe(10);
+    expect(lib.symbols.get_node_layout_unrounded(engine, 3, ptr(rect))).toBe(0);
+    expect(rect[0]).toBeCloseTo(200 / 3, 4);
+    expect(rect[2]).toBeCloseTo(100 / 3, 4);
+  });
+
+  it("should reject unknown nodes and a null output", () => {
+    const engine = createEngine();
+    expect(computeFromNodes(lib, engine, [{ style: { Width: 10, Height: 10 } }])).toBe(0);
+    const rect = new Float32Array(4);
+    expect(lib.symbols.get_node_layout_unrounded(engine, 42, ptr(rect))).toBe(-21);
+    expect(lib.symbols.get_node_layout_unrounded(engine, 0, null)).toBe(-34);
+  });
+});
+
+describe("Layout Engine FFI baselines", () => {
+  function baseline(engine: Pointer, id: number): number {
+    const out = new Float32Array(1);
+    expect(lib.symbols.get_node_baseline(engine, id, ptr(out))).toBe(0);
+    return out[0]!;
+  }
+
+  it("should report baselines that line up across baseline-aligned children", () => {
+    const engine = createEngine();
+    expect(
+      computeFromNodes(lib, engine, [
+        { style: { AlignItems: 3 }, children: [1, 2, 3] },
+        { style: { Width: 5, Height: 10 } },
+        { style: { Width: 5, Height: 4 } },
+        // A column takes its baseline from its first child, below the padding.
+        { style: { FlexDirection: 1, Width: 5, PaddingTop: 2 }, children: [4, 5] },
+        { style: { Height: 3 } },
+        { style: { Height: 5 } },
+      ]),
+    ).toBe(0);
+    const results = readResults(lib, engine);
+
+    // Leaves have no text, so their baseline is their bottom edge.
+    expect([1, 2, 4, 5].map((id) => baseline(engine, id))).toEqual([10, 4, 3, 5]);
+    expect(baseline(engine, 3)).toBe(5);
+    for (const id of [1, 2, 3]) {
+      expect(results.get(id)!.y + baseline(engine, id)).toBe(10);
+    }
+    expect(baseline(engine, 0)).toBe(10);
+  });
+
+  it("should reject unknown nodes and a null output", () => {
+    const engine = createEngine();
+    expect(computeFromNodes(lib, engine, [{ style: { Width: 10, Height: 10 } }])).toBe(0);
+    expect(lib.symbols.get_node_baseline(engine, 0, null)).toBe(-38);
+    expect(lib.symbols.get_node_baseline(engine, 9, ptr(new Float32Array(1)))).toBe(-21);
+  });
+});
+
+describe("Layout Engine FFI grid aspect ratio", () => {
+  // A single-column grid: the 40-wide track is the only definite size its items see. The
+  // expected rects match Taffy's grid output for the same trees.
+  const grid = (...items: TestNode["style"][]): TestNode[] => [
+    { style: { Display: 3, Width: 40 }, children: items.map((_, i) => i + 1) },
+    ...items.map((style) => ({ style })),
+  ];
+
+  it("should derive the auto axis of a grid item from its ratio", () => {
+    const engine = createEngine();
+    expect(computeFromNodes(lib, engine, grid({ AspectRatio: 2 }))).toBe(0);
+    let results = readResults(lib, engine);
+    expect(results.get(1)).toEqual({ x: 0, y: 0, width: 40, height: 20 });
+    expect(results.get(0)).toEqual({ x: 0, y: 0, width: 40, height: 20 });
+
+    // A definite height drives the width instead of the item stretching across its cell.
+    expect(computeFromNodes(lib, engine, grid({ AspectRatio: 2, Height: 10 }))).toBe(0);
+    expect(readResults(lib, engine).get(1)).toEqual({ x: 0, y: 0, width: 20, height: 10 });
+
+    expect(computeFromNodes(lib, engine, grid({ AspectRatio: 2, Width: 10 }))).toBe(0);
+    expect(readResults(lib, engine).get(1)).toEqual({ x: 0, y: 0, width: 10, height: 5 });
+
+    // Each implicit row sizes to its own item's ratio.
+    expect(computeFromNodes(lib, engine, grid({ AspectRatio: 4 }, { AspectRatio: 8 }))).toBe(0);
+    results = readResults(lib, engine);
+    expect(results.get(1)).toEqual({ x: 0, y: 0, width: 40, height: 10 });
+    expect(results.get(2)).toEqual({ x: 0, y: 10, width: 40, height: 5 });
+  });
+
+  it("should ignore the ratio when both sizes are definite or it is not positive", () => {
+    const engine = createEngine();
+    expect(computeFromNodes(lib, engine, grid({ AspectRatio: 2, Width: 10, Height: 10 }))).toBe(0);
+    expect(readResults(lib, engine).get(1)).toEqual({ x: 0, y: 0, width: 10, height: 10 });
+
+    const unset = grid({ AspectRatio: 0, Height: 3 }, { AspectRatio: -1, Height: 3 });
+    expect(computeFromNodes(lib, engine, unset)).toBe(0);
+    const results = readResults(lib, engine);
+    expect(results.get(1)).toEqual({ x: 0, y: 0, width: 40, height: 3 });
+    expect(results.get(2)).toEqual({ x: 0, y: 3, width: 40, height: 3 });
+  });
+
+  it("should clamp ratio-derived sizes by min and max, with min winning a conflict", () => {
+    const engine = createEngine();
+    const goldens: [TestNode["style"], { width: number; height: number }][] = [
+      // A max height stops the stretched width's ratio and pulls the width back with it.
+      [{ AspectRatio: 2, MaxHeight: 10 }, { width: 20, height: 10 }],
+      [{ AspectRatio: 2, MinHeight: 30 }, { width: 60, height: 30 }],
+      [{ AspectRatio: 2, MaxWidth: 30 }, { width: 30, height: 15 }],
+      [{ AspectRatio: 2, Height: 10, MaxWidth: 12 }, { width: 12, height: 6 }],
+      // Min wins over max, as in CSS, while the width still follows the max height's ratio.
+      [{ AspectRatio: 2, MinHeight: 30, MaxHeight: 10 }, { width: 20, height: 30 }],
+    ];
+    for (const [style, size] of goldens) {
+      expect(computeFromNodes(lib, engine, grid(style))).toBe(0);
+      expect(readResults(lib, engine).get(1)).toEqual({ x: 0, y: 0, ...size });
+    }
+  });
+});
+
+describe("Layout Engine FFI display contents", () => {
+  const row = { Width: 100, Height: 10 };
+  const cell = { Width: 10, Height: 10 };
+  const item = { Width: 20, Height: 10 };
+
+  it("should lay a contents node's children out as children of its parent", () => {
+    const engine = createEngine();
+    expect(
+      computeFromNodes(lib, engine, [
+        { style: row, children: [1, 2, 3] },
+        { style: cell },
+        // Its width is ignored: the node generates no box.
+        { style: { Display: 4, Width: 50 }, children: [4, 5] },
+        { style: cell },
+        { style: item },
+        { style: item },
+      ]),
+    ).toBe(0);
+    const results = readResults(lib, engine);
+    expect(results.get(2)).toEqual({ x: 0, y: 0, width: 0, height: 0 });
+    expect(results.get(4)).toEqual({ x: 10, y: 0, width: 20, height: 10 });
+    expect(results.get(5)).toEqual({ x: 30, y: 0, width: 20, height: 10 });
+    expect(results.get(3)).toEqual({ x: 50, y: 0, width: 10, height: 10 });
+  });
+
+  it("should flatten nested contents nodes", () => {
+    const engine = createEngine();
+    expect(
+      computeFromNodes(lib, engine, [
+        { style: { Width: 200, Height: 50 }, children: [7] },
+        { style: cell },
+        { style: { Display: 4 }, children: [4, 6] },
+        { style: cell },
+        { style: item },
+        { style: item },
+        { style: { Display: 4 }, children: [5] },
+        { style: row, children: [1, 2, 3] },
+      ]),
+    ).toBe(0);
+    const results = readResults(lib, engine);
+    expect(results.get(4)).toEqual({ x: 10, y: 0, width: 20, height: 10 });
+    expect(results.get(5)).toEqual({ x: 30, y: 0, width: 20, height: 10 });
+    expect(results.get(3)).toEqual({ x: 50, y: 0, width: 10, height: 10 });
+    expect(results.get(6)).toEqual({ x: 0, y: 0, width: 0, height: 0 });
+  });
+
+  it("should follow display changes, child lists and order from op batches", () => {
+    const engine = createEngine();
+    expect(
+      computeFromNodes(lib, engine, [
+        { style: row, children: [1, 2, 3] },
+        { style: cell },
+        { style: { Display: 4, Width: 50, Height: 10 }, children: [4, 5] },
+        { style: cell },
+        { style: item },
+        { style: item },
+      ]),
+    ).toBe(0);
+
+    const toFlex = new Float32Array([StyleProp.Display, 0]);
+    expect(applyOps(lib, engine, [LayoutOp.SetStylePartial, 2, 0, 1], toFlex)).toBe(0);
+    let results = readResults(lib, engine);
+    expect(results.get(2)).toEqual({ x: 10, y: 0, width: 50, height: 10 });
+    expect(results.get(4)).toEqual({ x: 0, y: 0, width: 20, height: 10 });
+    expect(results.get(3)).toEqual({ x: 60, y: 0, width: 10, height: 10 });
+
+    const toContents = new Float32Array([StyleProp.Display, 4]);
+    expect(applyOps(lib, engine, [LayoutOp.SetStylePartial, 2, 0, 1], toContents)).toBe(0);
+    results = readResults(lib, engine);
+    expect(results.get(4)).toEqual({ x: 10, y: 0, width: 20, height: 10 });
+    expect(results.get(3)).toEqual({ x: 50, y: 0, width: 10, height: 10 });
+
+    const first = new Float32Array([StyleProp.Order, -1]);
+    expect(applyOps(lib, engine, [LayoutOp.SetStylePartial, 5, 0, 1], first)).toBe(0);
+    results = readResults(lib, engine);
+    expect(results.get(5)).toEqual({ x: 10, y: 0, width: 20, height: 10 });
+    expect(results.get(4)).toEqual({ x: 30, y: 0, width: 20, height: 10 });
+
+    expect(applyOps(lib, engine, [LayoutOp.SetChildren, 2, 0, 1], undefined, [5])).toBe(0);
+    results = readResults(lib, engine);
+    expect(results.get(5)).toEqual({ x: 10, y: 0, width: 20, height: 10 });
+    expect(results.get(3)).toEqual({ x: 30, y: 0, width: 10, height: 10 });
+  });
+});
+
+describe("Layout Engine FFI auto margins", () => {
+  it("should center a fixed-width child between auto side margins", () => {
+    const engine = createEngine();
+    const centered = { Width: 40, Height: 10, MarginLeft: NaN, MarginRight: NaN };
+    expect(
+      computeFromNodes(lib, engine, [
+        { style: { Width: 100, Height: 20 }, children: [1] },
+        { style: centered },
+      ]),
+    ).toBe(0);
+    expect(readResults(lib, engine).get(1)).toEqual({ x: 30, y: 0, width: 40, height: 10 });
+
+    // Block layout centers the same way; a single auto margin pushes the node to the end.
+    expect(
+      computeFromNodes(lib, engine, [
+        { style: { Display: 2, Width: 100 }, children: [1, 2] },
+        { style: centered },
+        { style: { Width: 40, Height: 10, MarginLeft: NaN } },
+      ]),
+    ).toBe(0);
+    const results = readResults(lib, engine);
+    expect(results.get(1)).toEqual({ x: 30, y: 0, width: 40, height: 10 });
+    expect(results.get(2)).toEqual({ x: 60, y: 10, width: 40, height: 10 });
+  });
+});
+
+describe("Layout Engine FFI flex lines", () => {
+  // 10 + 10 | 12 + 12 | 25 in a 30-wide row with 1-cell gaps.
+  const tree = (flexWrap: number, flexDirection = 0): TestNode[] => [
+    {
+      style: { Width: 30, FlexWrap: flexWrap, FlexDirection: flexDirection, GapColumn: 1 },
+      children: [1, 2, 3, 4, 5],
+    },
+    ...[10, 10, 12, 12, 25].map((width) => ({ style: { Width: width, Height: 1 } })),
+  ];
+
+  it("should count the lines a wrapping row broke into", () => {
+    const engine = createEngine();
+    expect(computeFromNodes(lib, engine, tree(1))).toBe(0);
+    expect(lib.symbols.get_flex_line_count(engine, 0)).toBe(3);
+
+    // Reversed rows fill their lines from the other end.
+    expect(computeFromNodes(lib, engine, tree(1, 2))).toBe(0);
+    expect(lib.symbols.get_flex_line_count(engine, 0)).toBe(3);
+  });
+
+  it("should keep items that overlap through negative margins on one line", () => {
+    const engine = createEngine();
+    // Each item takes 12 - 5 = 7 cells, so all three fit even though each starts before
+    // the previous one ends.
+    const overlapping = (flexDirection: number): TestNode[] => [
+      {
+        style: { Width: 30, FlexWrap: 1, FlexDirection: flexDirection, GapColumn: 1 },
+        children: [1, 2, 3],
+      },
+      ...[1, 2, 3].map(() => ({ style: { Width: 12, Height: 1, MarginLeft: -5 } })),
+    ];
+
+    for (const flexDirection of [0, 2]) {
+      expect(computeFromNodes(lib, engine, overlapping(flexDirection))).toBe(0);
+      expect(lib.symbols.get_flex_line_count(engine, 0)).toBe(1);
+    }
+    expect(readResults(lib, engine).get(2)).toEqual({ x: 10, y: 0, width: 12, height: 1 });
+  });
+
+  it("should report a single line for containers that do not wrap", () => {
+    const engine = createEngine();
+    expect(computeFromNodes(lib, engine, tree(0))).toBe(0);
+    expect(lib.symbols.get_flex_line_count(engine, 0)).toBe(1);
+    expect(lib.symbols.get_flex_line_count(engine, 1)).toBe(1);
+    expect(lib.symbols.get_flex_line_count(engine, 9)).toBe(-21);
+  });
+});
+
+describe("Layout Engine FFI absolute layout", () => {
+  it("should place a deeply nested node at the sum of its ancestors' offsets", () => {
+    const engine = createEngine();
+    expect(
+      computeFromNodes(lib, engine, [
+        { style: { Width: 100, Height: 100, PaddingLeft: 2, PaddingTop: 2 }, children: [1] },
+        { style: { MarginLeft: 3, PaddingLeft: 4, PaddingTop: 4 }, children: [2] },
+        { style: { MarginTop: 5, PaddingLeft: 1, FlexDirection: 1 }, children: [4, 3] },
+        { style: { Width: 5, Height: 5 } },
+        { style: { Width: 7, Height: 6 } },
+      ]),
+    ).toBe(0);
+    const results = readResults(lib, engine);
+    const sum = (axis: "x" | "y") =>
+      [3, 2, 1].reduce((total, id) => total + (results.get(id)?.[axis] ?? NaN), 0);
+
+    const out = new Float32Array(4);
+    expect(lib.symbols.get_absolute_layout(engine, 3, ptr(out))).toBe(0);
+    expect(Array.from(out)).toEqual([sum("x"), sum("y"), 5, 5]);
+    expect(Array.from(out)).toEqual([10, 17, 5, 5]);
+
+    expect(lib.symbols.get_absolute_layout(engine, 9, ptr(out))).toBe(-21);
+    expect(lib.symbols.get_absolute_layout(engine, 3, null)).toBe(-34);
+  });
+});
+
+describe("Layout Engine FFI hit testing", () => {
+  const square = (size: number) => ({ Width: size, Height: size });
+  const overlay = (left: number, top: number) => ({
+    ...square(30),
+    PositionType: 1,
+    MarginLeft: left,
+    MarginTop: top,
+  });
+
+  function hitTest(engine: Pointer, root: number, x: number, y: number) {
+    const out = new Uint32Array(1);
+    const status = lib.symbols.hit_test(engine, root, x, y, ptr(out));
+    return status === 1 ? out[0] : status;
+  }
+
+  function populate(engine: Pointer, tree: TestNode[]) {
+    expect(computeFromNodes(lib, engine, tree)).toBe(0);
+  }
+
+  const tree = (hidden = 0): TestNode[] => [
+    { style: { ...square(100), AlignItems: 0 }, children: [1, 3, 4, 5, 6] },
+    { style: square(50), children: [2] },
+    { style: square(20) },
+    { style: { ...square(30), Visibility: hidden } },
+    { style: overlay(40, 10) },
+    { style: overlay(50, 20) },
+    { style: { ...square(100), Display: 1, PositionType: 1 } },
+  ];
+
+  it("should return the deepest node at a point", () => {
+    const engine = createEngine();
+    populate(engine, tree());
+    expect(hitTest(engine, 0, 5, 5)).toBe(2);
+    expect(hitTest(engine, 0, 30, 30)).toBe(1);
+    expect(hitTest(engine, 0, 75, 5)).toBe(3);
+    // Node 6 would cover everything, but it is display: none.
+    expect(hitTest(engine, 0, 95, 95)).toBe(0);
+    expect(hitTest(engine, 0, 200, 200)).toBe(0);
+    // Only node 1's subtree is searched.
+    expect(hitTest(engine, 1, 30, 30)).toBe(1);
+    expect(hitTest(engine, 1, 60, 10)).toBe(0);
+  });
+
+  it("should return the topmost of overlapping positioned nodes", () => {
+    const engine = createEngine();
+    populate(engine, tree());
+    // Node 4 paints over the in-flow node 1, and node 5 over node 4.
+    expect(hitTest(engine, 0, 45, 15)).toBe(4);
+    expect(hitTest(engine, 0, 55, 25)).toBe(5);
+    expect(hitTest(engine, 0, 60, 45)).toBe(5);
+  });
+
+  it("should skip hidden nodes and reject bad arguments", () => {
+    const engine = createEngine();
+    populate(engine, tree(1));
+    expect(hitTest(engine, 0, 75, 5)).toBe(0);
+    expect(hitTest(engine, 9, 5, 5)).toBe(-21);
+    expect(lib.symbols.hit_test(engine, 0, 5, 5, null)).toBe(-49);
+  });
+});
+
+describe("Layout Engine FFI bounding boxes", () => {
+  function boundingBox(engine: Pointer, ids: number[]) {
+    const idsBuf = new Uint32Array(ids);
+    const out = new Float32Array(4);
+    const status = lib.symbols.get_bounding_box(
+      engine,
+      idsBuf.length > 0 ? ptr(idsBuf) : null,
+      idsBuf.length,
+      ptr(out),
+    );
+    return { status, box: Array.from(out) };
+  }
+
+  it("should union nested and absolutely positioned nodes in root coordinates", () => {
+    const engine = createEngine();
+    expect(
+      computeFromNodes(lib, engine, [
+        {
+          style: { Width: 100, Height: 100, FlexDirection: 1, PaddingLeft: 5, PaddingTop: 5 },
+          children: [1, 2, 4],
+        },
+        { style: { Height: 20, PaddingLeft: 3, PaddingTop: 2 }, children: [3] },
+        { style: { Width: 10, Height: 10, MarginLeft: 50 } },
+        { style: { Width: 10, Height: 10 } },
+        { style: { Width: 30, Height: 8, PositionType: 1, MarginLeft: 60, MarginTop: 70 } },
+      ]),
+    ).toBe(0);
+
+    // Node 3 is reported at (3, 2) inside node 1, which itself sits at (5, 5).
+    expect(boundingBox(engine, [3])).toEqual({ status: 0, box: [8, 7, 10, 10] });
+    // Node 4 spans (65, 75) to (95, 83).
+    expect(boundingBox(engine, [3, 2, 4])).toEqual({ status: 0, box: [8, 7, 87, 76] });
+    expect(boundingBox(engine, [])).toEqual({ status: 0, box: [0, 0, 0, 0] });
+    expect(boundingBox(engine, [3, 9]).status).toBe(-21);
+    expect(lib.symbols.get_bounding_box(engine, null, 0, null)).toBe(-48);
+  });
+});
+
+describe("Layout Engine FFI snapshots", () => {
+  function exportSnapshot(engine: Pointer): Uint8Array {
+    const bytes = new Uint8Array(Number(lib.symbols.export_snapshot(engine, null, 0)));
+    lib.symbols.export_snapshot(engine, ptr(bytes), bytes.length);
+    return bytes;
+  }
+
+  it("should round-trip the input tree through export and import", () => {
+    const engine = createEngine();
+    expect(
+      computeFromNodes(lib, engine, [
+        { style: { Width: 100, Height: 50, FlexDirection: 1, PaddingLeft: 3 }, children: [1, 3] },
+        { style: { Width: 40, Height: 20, MarginTop: 5 }, children: [2, 4] },
+        {
+          style: { PositionType: 1, AnchorRoot: 1, Width: 10, Height: 5, MarginLeft: 2 },
+        },
+        { style: { FlexGrow: 1 } },
+        { style: { Width: 7, Height: 3 } },
+      ]),
+    ).toBe(0);
+    const snapshot = exportSnapshot(engine);
+    const parsed = JSON.parse(new TextDecoder().decode(snapshot));
+    expect(parsed.nodes.map((node: { id: number }) => node.id)).toEqual([0, 1, 2, 3, 4]);
+    // Unset lanes are null, and the anchored overlay is still listed under its parent.
+    expect(parsed.nodes[3].style[StyleProp.Width]).toBeNull();
+    expect(parsed.nodes[1].children).toEqual([2, 4]);
+
+    const restored = createEngine();
+    expect(lib.symbols.import_snapshot(restored, ptr(snapshot), snapshot.length)).toBe(0);
+    expect(readRawResults(lib, restored)).toEqual(readRawResults(lib, engine));
+    expect(exportSnapshot(restored)).toEqual(snapshot);
+  });
+
+  it("should keep the current tree when an import fails", () => {
+    const engine = createEngine();
+    expect(computeFromNodes(lib, engine, [{ style: { Width: 10, Height: 10 } }])).toBe(0);
+    const results = readRawResults(lib, engine);
+
+    const importJson = (json: string) => {
+      const bytes = new TextEncoder().encode(json);
+      return lib.symbols.import_snapshot(engine, ptr(bytes), bytes.length);
+    };
+    expect(importJson("{")).toBe(-23);
+    expect(importJson('{"nodes":[{"id":0,"style":[1],"children":[]}]}')).toBe(-2);
+    const style = new Array(STYLE_STRIDE).fill(0);
+    expect(importJson(JSON.stringify({ nodes: [{ id: 1, style, children: [] }] }))).toBe(-36);
+    expect(readRawResults(lib, engine)).toEqual(results);
+  });
+});
+
+describe("Layout Engine FFI status messages", () => {
+  const message = (status: number) => String(lib.symbols.layout_engine_error_string(status));
+
+  it("should describe the status of each failed call", () => {
+    const engine = createEngine();
+    const badStride = lib.symbols.compute_layout_from_buffers(
+      engine,
+      ptr(new Float32Array(STYLE_STRIDE + 1)),
+      STYLE_STRIDE + 1,
+      null,
+      0,
+    );
+    expect(badStride).toBe(-2);
+    expect(message(badStride)).toBe("style buffer length is not a multiple of the style stride");
+
+    expect(computeFromNodes(lib, engine, [{ style: { Width: 10, Height: 10 } }])).toBe(0);
+    const unknownRoot = lib.symbols.compute_layout_for_root(engine, 7, NaN, NaN);
+    expect(unknownRoot).toBe(-21);
+    expect(message(unknownRoot)).toBe("unknown node id");
+
+    const infinite = computeFromNodes(lib, engine, [{ style: { Width: Infinity } }]);
+    expect(infinite).toBe(-28);
+    expect(message(infinite)).toBe("infinite style value");
+
+    const unknownChild = applyOps(lib, engine, [LayoutOp.SetChildren, 0, 0, 1], undefined, [9]);
+    expect(unknownChild).toBe(-18);
+    expect(message(unknownChild)).toBe("SetChildren references an unknown child");
+  });
+});
+
+describe("Layout Engine FFI tree dump", () => {
+  it("should dump ids, nesting and computed rects", () => {
+    const engine = createEngine();
+    expect(
+      computeFromNodes(lib, engine, [
+        { style: { Width: 100, Height: 40, FlexDirection: 1 }, children: [1, 2] },
+        { style: { Height: 10 }, children: [3] },
+        { style: { FlexGrow: 1 } },
+        { style: { Width: 20, PositionType: 1 } },
+      ]),
+    ).toBe(0);
+
+    const len = Number(lib.symbols.dump_tree(engine, 0, null, 0));
+    expect(len).toBeGreaterThan(0);
+    const bytes = new Uint8Array(len);
+    expect(Number(lib.symbols.dump_tree(engine, 0, ptr(bytes), bytes.length))).toBe(len);
+
+    const lines = new TextDecoder().decode(bytes).trimEnd().split("\n");
+    expect(lines).toEqual([
+      "#0 Flex Column [x: 0 y: 0 w: 100 h: 40]",
+      "  #1 Flex Row [x: 0 y: 0 w: 100 h: 10]",
+      "    #3 Flex Row Absolute [x: 0 y: 0 w: 20 h: 0]",
+      "  #2 Flex Row grow=1 [x: 0 y: 10 w: 100 h: 30]",
+    ]);
+
+    expect(Number(lib.symbols.dump_tree(engine, 99, null, 0))).toBe(0);
+  });
+});
+
+describe("Layout Engine FFI results order", () => {
+  function jsIdColumn(engine: Pointer): number[] {
+    const raw = readRawResults(lib, engine);
+    const ids: number[] = [];
+    for (let i = 0; i < raw.length; i += RESULT_STRIDE) ids.push(raw[i]!);
+    return ids;
+  }
+
+  it("should emit buffer-path results in ascending js id order", () => {
+    const engine = createEngine();
+    const leafCount = 64;
+    const tree: TestNode[] = [
+      {
+        style: { Width: 100 },
+        children: Array.from({ length: leafCount }, (_, i) => leafCount - i),
+      },
+    ];
+    for (let i = 0; i < leafCount; i++) tree.push({ style: { Width: 1, Height: 1 } });
+    expect(computeFromNodes(lib, engine, tree)).toBe(0);
+
+    expect(jsIdColumn(engine)).toEqual(Array.from({ length: leafCount + 1 }, (_, i) => i));
+  });
+
+  it("should emit op-path results in ascending js id order regardless of creation order", () => {
+    const engine = createEngine();
+    const styles = encodeStyles([{ Width: 100, Height: 10 }, { Width: 5, Height: 5 }]);
+    const ops = [
+      ...[9, 2, 7, 5].flatMap((id) => [LayoutOp.CreateLeaf, id, STYLE_STRIDE]),
+      LayoutOp.CreateLeaf,
+      0,
+      0,
+      LayoutOp.SetChildren,
+      0,
+      0,
+      4,
+    ];
+    expect(applyOps(lib, engine, ops, styles, [9, 2, 7, 5])).toBe(0);
+
+    expect(jsIdColumn(engine)).toEqual([0, 2, 5, 7, 9]);
+  });
+});
+
+describe("Layout Engine FFI op batches", () => {
+  const styles = encodeStyles([
+    { Width: 100, Height: 10 },
+    { Width: 10, Height: 10 },
+    { Width: 20, Height: 10 },
+    { Width: 40, Height: 5 },
+  ]);
+
+  function populate(engine: Pointer) {
+    const ops = [
+      ...[0, 1, 2].flatMap((id) => [LayoutOp.CreateLeaf, id, id * STYLE_STRIDE]),
+      ...[LayoutOp.SetChildren, 0, 0, 2],
+    ];
+    expect(applyOps(lib, engine, ops, styles, [1, 2])).toBe(0);
+  }
+
+  it("should recreate a node removed earlier in the same batch", () => {
+    const engine = createEngine();
+    populate(engine);
+
+    const ops = [
+      ...[LayoutOp.RemoveNode, 2],
+      ...[LayoutOp.CreateLeaf, 2, 3 * STYLE_STRIDE],
+      ...[LayoutOp.SetChildren, 0, 0, 2],
+    ];
+    expect(applyOps(lib, engine, ops, styles, [1, 2])).toBe(0);
+    const results = readResults(lib, engine);
+    expect(results.get(1)).toEqual({ x: 0, y: 0, width: 10, height: 10 });
+    expect(results.get(2)).toEqual({ x: 10, y: 0, width: 40, height: 5 });
+  });
+
+  it("should reject creating a node whose id is still live", () => {
+    const engine = createEngine();
+    populate(engine);
+    const before = readResults(lib, engine);
+
+    expect(applyOps(lib, engine, [LayoutOp.CreateLeaf, 2, 3 * STYLE_STRIDE], styles)).toBe(-51);
+    const ops = [
+      ...[LayoutOp.CreateLeaf, 3, 3 * STYLE_STRIDE],
+      ...[LayoutOp.CreateLeaf, 3, 3 * STYLE_STRIDE],
+    ];
+    expect(applyOps(lib, engine, ops, styles)).toBe(-51);
+    expect(readResults(lib, engine)).toEqual(before);
+
+    // The old node stays wired to its parent and is still the one laid out.
+    expect(applyOps(lib, engine, [LayoutOp.UpdateStyle, 2, 3 * STYLE_STRIDE], styles)).toBe(0);
+    expect(readResults(lib, engine).get(2)).toEqual({ x: 10, y: 0, width: 40, height: 5 });
+  });
+
+  it("should reject a batch that references a node it removed, before applying any op", () => {
+    const engine = createEngine();
+    populate(engine);
+    const before = readResults(lib, engine);
+
+    const ops = [
+      ...[LayoutOp.UpdateStyle, 1, 3 * STYLE_STRIDE],
+      ...[LayoutOp.RemoveNode, 2],
+      ...[LayoutOp.SetChildren, 0, 0, 2],
+    ];
+    expect(applyOps(lib, engine, ops, styles, [1, 2])).toBe(-18);
+    expect(applyOps(lib, engine, [LayoutOp.UpdateStyle, 2, 3 * STYLE_STRIDE], styles)).toBe(0);
+    const after = readResults(lib, engine);
+    expect(after.get(1)).toEqual(before.get(1));
+    expect(after.get(2)).toEqual({ x: 10, y: 0, width: 40, height: 5 });
+  });
+
+  it("should reject a children list that repeats a child on either path", () => {
+    const engine = createEngine();
+    populate(engine);
+    const before = readResults(lib, engine);
+
+    expect(applyOps(lib, engine, [LayoutOp.SetChildren, 0, 0, 2], styles, [1, 1])).toBe(-50);
+    expect(readResults(lib, engine)).toEqual(before);
+
+    const nodes = [{ children: [1, 1] }, { style: { Width: 10, Height: 10 } }];
+    expect(computeFromNodes(lib, engine, nodes)).toBe(-50);
+    expect(readResults(lib, engine)).toEqual(before);
+  });
+
+  it("should tell a tree numbered from 1 apart from an empty one", () => {
+    const engine = createEngine();
+    expect(applyOps(lib, engine, [])).toBe(0);
+    expect(readRawResults(lib, engine)).toEqual(new Float32Array(0));
+
+    const ops = [
+      ...[1, 2, 3].flatMap((id) => [LayoutOp.CreateLeaf, id, id * STYLE_STRIDE]),
+      ...[LayoutOp.SetChildren, 1, 0, 2],
+    ];
+    expect(applyOps(lib, engine, ops, styles, [2, 3])).toBe(-36);
+    expect(readRawResults(lib, engine)).toEqual(new Float32Array(0));
+  });
+
+  it("should leave the engine untouched when a later op in the batch fails", () => {
+    const engine = createEngine();
+    populate(engine);
+    const dump = () => {
+      const bytes = new Uint8Array(Number(lib.symbols.dump_tree(engine, 0, null, 0)));
+      lib.symbols.dump_tree(engine, 0, ptr(bytes), bytes.length);
+      return new TextDecoder().decode(bytes);
+    };
+    const outline = dump();
+    const results = readRawResults(lib, engine);
+
+    const ops = [
+      ...[LayoutOp.UpdateStyle, 1, 3 * STYLE_STRIDE],
+      ...[LayoutOp.CreateLeaf, 5, STYLE_STRIDE],
+      ...[LayoutOp.UpdateStyle, 9, 0],
+      ...[LayoutOp.SetChildren, 0, 0, 3],
+      ...[LayoutOp.RemoveNode, 2],
+    ];
+    expect(applyOps(lib, engine, ops, styles, [1, 2, 5])).toBe(-14);
+    expect(dump()).toBe(outline);
+    expect(readRawResults(lib, engine)).toEqual(results);
+
+    expect(applyOps(lib, engine, [LayoutOp.RemoveNode, 0])).toBe(-36);
+    expect(dump()).toBe(outline);
+    expect(readRawResults(lib, engine)).toEqual(results);
+  });
+
+  it("should remove a node and all its descendants with RemoveSubtree", () => {
+    const engine = createEngine();
+    populate(engine);
+    const grandchildren = [
+      ...[3, 4].flatMap((id) => [LayoutOp.CreateLeaf, id, STYLE_STRIDE]),
+      ...[LayoutOp.SetChildren, 2, 0, 1],
+      ...[LayoutOp.SetChildren, 3, 1, 1],
+    ];
+    expect(applyOps(lib, engine, grandchildren, styles, [3, 4])).toBe(0);
+    expect([...readResults(lib, engine).keys()]).toEqual([0, 1, 2, 3, 4]);
+
+    // Gone for the rest of the batch, descendants included.
+    const touchDescendant = [LayoutOp.RemoveSubtree, 2, LayoutOp.UpdateStyle, 4, 0];
+    expect(applyOps(lib, engine, touchDescendant, styles)).toBe(-14);
+
+    const ops = [...[LayoutOp.RemoveSubtree, 2], ...[LayoutOp.SetChildren, 0, 0, 1]];
+    expect(applyOps(lib, engine, ops, styles, [1])).toBe(0);
+    expect([...readResults(lib, engine).keys()]).toEqual([0, 1]);
+    expect([2, 3, 4].map((id) => lib.symbols.layout_engine_has_node(engine, id))).toEqual([
+      0, 0, 0,
+    ]);
+  });
+
+  it("should keep a node moved out of a subtree before RemoveSubtree in the same batch", () => {
+    const engine = createEngine();
+    populate(engine);
+    const grandchildren = [
+      ...[3, 4].flatMap((id) => [LayoutOp.CreateLeaf, id, STYLE_STRIDE]),
+      ...[LayoutOp.SetChildren, 2, 0, 1],
+      ...[LayoutOp.SetChildren, 3, 1, 1],
+    ];
+    expect(applyOps(lib, engine, grandchildren, styles, [3, 4])).toBe(0);
+
+    // 4 leaves 2's subtree first, so it survives and can still be wired afterwards.
+    const ops = [
+      ...[LayoutOp.SetChildren, 1, 0, 1],
+      ...[LayoutOp.RemoveSubtree, 2],
+      ...[LayoutOp.SetChildren, 1, 0, 1],
+    ];
+    expect(applyOps(lib, engine, ops, styles, [4])).toBe(0);
+    expect([...readResults(lib, engine).keys()]).toEqual([0, 1, 4]);
+    expect([2, 3, 4].map((id) => lib.symbols.layout_engine_has_node(engine, id))).toEqual([
+      0, 0, 1,
+    ]);
+  });
+
+  it("should patch only the lanes named by SetStylePartial", () => {
+    const engine = createEngine();
+    const tree: TestNode[] = [
+      { style: { Width: 100, Height: 10 }, children: [1, 2] },
+      { style: { Width: 10, Height: 4, MarginLeft: 2 } },
+      { style: { Width: 20, Height: 5 } },
+    ];
+    expect(computeFromNodes(lib, engine, tree)).toBe(0);
+
+    const pairs = new Float32Array([StyleProp.FlexGrow, 1]);
+    expect(applyOps(lib, engine, [LayoutOp.SetStylePartial, 1, 0, 1], pairs)).toBe(0);
+    const results = readResults(lib, engine);
+    // Grows into the free space, still 4 tall and 2 cells in.
+    expect(results.get(1)).toEqual({ x: 2, y: 0, width: 78, height: 4 });
+    expect(results.get(2)).toEqual({ x: 80, y: 0, width: 20, height: 5 });
+  });
+
+  it("should reject malformed SetStylePartial ops", () => {
+    const engine = createEngine();
+    populate(engine);
+    const patch = (ops: number[], pairs: number[]) =>
+      applyOps(lib, engine, ops, new Float32Array(pairs));
+
+    expect(patch([LayoutOp.SetStylePartial, 1, 0], [StyleProp.FlexGrow, 1])).toBe(-40);
+    expect(patch([LayoutOp.SetStylePartial, 1, 0, 2], [StyleProp.FlexGrow, 1])).toBe(-41);
+    expect(patch([LayoutOp.SetStylePartial, 9, 0, 1], [StyleProp.FlexGrow, 1])).toBe(-42);
+    expect(patch([LayoutOp.SetStylePartial, 1, 0, 1], [STYLE_STRIDE, 1])).toBe(-43);
+    expect(patch([LayoutOp.SetStylePartial, 1, 0, 1], [StyleProp.Width, Infinity])).toBe(-28);
+  });
+
+  it("should defer layout across apply_ops batches until compute_current", () => {
+    const engine = createEngine();
+    // The leaves arrive before their root does.
+    const leaves = [LayoutOp.CreateLeaf, 1, STYLE_STRIDE, LayoutOp.CreateLeaf, 2, 2 * STYLE_STRIDE];
+    expect(applyOps(lib, engine, leaves, styles, [], false)).toBe(0);
+    const root = [LayoutOp.CreateLeaf, 0, 0, LayoutOp.SetChildren, 0, 0, 2];
+    expect(applyOps(lib, engine, root, styles, [1, 2], false)).toBe(0);
+    expect(Number(lib.symbols.get_results_len(engine))).toBe(0);
+
+    expect(lib.symbols.compute_current(engine, 0, NaN, NaN)).toBe(0);
+    const results = readResults(lib, engine);
+    expect(results.get(1)).toEqual({ x: 0, y: 0, width: 10, height: 10 });
+    expect(results.get(2)).toEqual({ x: 10, y: 0, width: 20, height: 10 });
+
+    expect(lib.symbols.compute_current(engine, 9, NaN, NaN)).toBe(-21);
+    expect(lib.symbols.compute_current(engine, 0, Infinity, NaN)).toBe(-33);
+  });
+
+  it("should report which ids exist, so a reconciler can pick create or update", () => {
+    const engine = createEngine();
+    expect(lib.symbols.layout_engine_has_node(engine, 0)).toBe(0);
+    populate(engine);
+    expect([0, 1, 2, 3].map((id) => lib.symbols.layout_engine_has_node(engine, id))).toEqual([
+      1, 1, 1, 0,
+    ]);
+
+    expect(applyOps(lib, engine, [LayoutOp.RemoveNode, 2])).toBe(0);
+    expect(lib.symbols.layout_engine_has_node(engine, 2)).toBe(0);
+    expect(lib.symbols.layout_engine_has_node(null, 0)).toBe(-1);
+  });
+});
+
+describe("Layout Engine FFI JSON input", () => {
+  function computeFromJson(engine: Pointer, json: string): number {
+    const bytes = new TextEncoder().encode(json);
+    return lib.symbols.compute_layout_from_json(engine, ptr(bytes), bytes.length);
+  }
+
+  it("should match the buffer path for the same tree", () => {
+    const jsonEngine = createEngine();
+    const tree = {
+      width: 200,
+      height: 100,
+      flexDirection: "row",
+      padding: 10,
+      gap: 10,
+      children: [
+        { width: 50, height: 50 },
+        { flexGrow: 1, height: "auto", children: [{ height: 5 }] },
+      ],
+    };
+    expect(computeFromJson(jsonEngine, JSON.stringify(tree))).toBe(0);
+
+    const bufferEngine = createEngine();
+    expect(
+      computeFromNodes(lib, bufferEngine, [
+        {
+          style: {
+            Width: 200,
+            Height: 100,
+            PaddingLeft: 10,
+            PaddingRight: 10,
+            PaddingTop: 10,
+            PaddingBottom: 10,
+            GapRow: 10,
+            GapColumn: 10,
+          },
+          children: [1, 2],
+        },
+        { style: { Width: 50, Height: 50 } },
+        { style: { FlexGrow: 1 }, children: [3] },
+        { style: { Height: 5 } },
+      ]),
+    ).toBe(0);
+
+    const results = readResults(lib, jsonEngine);
+    expect(results).toEqual(readResults(lib, bufferEngine));
+    expect(results.get(2)).toEqual({ x: 70, y: 10, width: 120, height: 80 });
+  });
+
+  it("should agree with the buffer path on which gap is which axis", () => {
+    // `width` is the column gap between items on a line, `height` the row gap between lines.
+    const jsonEngine = createEngine();
+    const tree = {
+      width: 25,
+      flexWrap: "wrap",
+      gap: { width: 5, height: 2 },
+      children: [1, 2, 3].map(() => ({ width: 10, height: 3 })),
+    };
+    expect(computeFromJson(jsonEngine, JSON.stringify(tree))).toBe(0);
+
+    const bufferEngine = createEngine();
+    expect(
+      computeFromNodes(lib, bufferEngine, [
+        { style: { Width: 25, FlexWrap: 1, GapRow: 2, GapColumn: 5 }, children: [1, 2, 3] },
+        ...[1, 2, 3].map(() => ({ style: { Width: 10, Height: 3 } })),
+      ]),
+    ).toBe(0);
+
+    const results = readResults(lib, jsonEngine);
+    expect(results).toEqual(readResults(lib, bufferEngine));
+    expect(results.get(2)).toEqual({ x: 15, y: 0, width: 10, height: 3 });
+    expect(results.get(3)).toEqual({ x: 0, y: 5, width: 10, height: 3 });
+    expect(results.get(0)).toEqual({ x: 0, y: 0, width: 25, height: 8 });
+  });
+
+  it("should reject malformed JSON", () => {
+    const engine = createEngine();
+    expect(computeFromJson(engine, "{ not json")).toBe(-23);
+  });
+
+  it("should read a percentage flexBasis", () => {
+    const engine = createEngine();
+    const tree = { width: 80, height: 1, children: [{ flexBasis: "50%" }, { flexBasis: "auto" }] };
+    expect(computeFromJson(engine, JSON.stringify(tree))).toBe(0);
+    const results = readResults(lib, engine);
+    expect(results.get(1)).toEqual({ x: 0, y: 0, width: 40, height: 1 });
+    expect(results.get(2)).toEqual({ x: 40, y: 0, width: 0, height: 1 });
+  });
+
+  it("should accept only auto as a margin keyword", () => {
+    const engine = createEngine();
+    const tree = (margin: unknown) =>
+      JSON.stringify({ width: 100, height: 20, children: [{ width: 40, height: 10, margin }] });
+    expect(computeFromJson(engine, tree(["auto", "auto", 0, 0]))).toBe(0);
+    expect(readResults(lib, engine).get(1)).toEqual({ x: 30, y: 0, width: 40, height: 10 });
+
+    expect(computeFromJson(engine, tree("10%"))).toBe(-23);
+    expect(computeFromJson(engine, tree(["auto", "centre", 0, 0]))).toBe(-23);
+  });
+});
@@ -0,0 +1,322 @@
+import { dlopen, FFIType, ptr, suffix, toArrayBuffer, type Pointer } from "bun:ffi";
+import path from "node:path";
+
+export function resolveDevLibPath() {
+  const libName = "liblayout_engine";
+  return path.join(
+    process.cwd(),
+    "src",
+    "layout-engine",
+    "target",
+    "release",
+    `${libName}.${suffix}`,
+  );
+}
+
+// --- Data Layout Constants (must match Rust) ---
+// prettier-ignore
+export enum StyleProp {
+  Display, PositionType, FlexDirection, FlexWrap,
+  JustifyContent, AlignItems, AlignSelf,
+  FlexGrow, FlexShrink, FlexBasis,
+  Width, Height, MinWidth, MinHeight, MaxWidth, MaxHeight,
+  MarginLeft, MarginRight, MarginTop, MarginBottom,
+  PaddingLeft, PaddingRight, PaddingTop, PaddingBottom,
+  GapRow, GapColumn,
+  ChildrenCount, ChildrenOffset,
+  OverflowX, OverflowY, ScrollbarWidth,
+  BoxSizing, Direction, AnchorRoot,
+  AlignContent, JustifyItems, JustifySelf,
+  GapRowUnit, GapColumnUnit, Order, AspectRatio, Visibility,
+  FlexBasisUnit,
+  TotalProps,
+}
+export const STYLE_STRIDE = StyleProp.TotalProps;
+export const RESULT_STRIDE = 5;
+
+export function openEngineLib() {
+  return dlopen(resolveDevLibPath(), {
+    create_engine: { args: [], returns: FFIType.ptr },
+    create_engine_with_capacity: { args: [FFIType.u64], returns: FFIType.ptr },
+    clone_engine: { args: [FFIType.ptr], returns: FFIType.ptr },
+    destroy_engine: { args: [FFIType.ptr], returns: FFIType.void },
+    reset_engine: { args: [FFIType.ptr], returns: FFIType.void },
+    trim_engine_memory: { args: [FFIType.ptr], returns: FFIType.void },
+    compute_layout_from_buffers: {
+      args: [FFIType.ptr, FFIType.ptr, FFIType.u64, FFIType.ptr, FFIType.u64],
+      returns: FFIType.i32,
+    },
+    compute_layout_from_buffers_checked: {
+      args: [FFIType.ptr, FFIType.ptr, FFIType.u64, FFIType.ptr, FFIType.u64, FFIType.u32],
+      returns: FFIType.i32,
+    },
+    compute_layout_from_buffers_with_ids: {
+      args: [
+        FFIType.ptr,
+        FFIType.ptr,
+        FFIType.u64,
+        FFIType.ptr,
+        FFIType.u64,
+        FFIType.ptr,
+        FFIType.u64,
+      ],
+      returns: FFIType.i32,
+    },
+    compute_layout_forest_from_buffers: {
+      args: [FFIType.ptr, FFIType.ptr, FFIType.u64, FFIType.ptr, FFIType.u64],
+      returns: FFIType.i32,
+    },
+    compute_layout_into: {
+      args: [
+        FFIType.ptr,
+        FFIType.ptr,
+        FFIType.u64,
+        FFIType.ptr,
+        FFIType.u64,
+        FFIType.ptr,
+        FFIType.u64,
+      ],
+      returns: FFIType.i32,
+    },
+    compute_layout_and_emit: {
+      args: [
+        FFIType.ptr,
+        FFIType.ptr,
+        FFIType.u64,
+        FFIType.ptr,
+        FFIType.u64,
+        FFIType.ptr,
+        FFIType.ptr,
+      ],
+      returns: FFIType.i32,
+    },
+    compute_layout_from_json: {
+      args: [FFIType.ptr, FFIType.ptr, FFIType.u64],
+      returns: FFIType.i32,
+    },
+    apply_ops_and_compute: {
+      args: [
+        FFIType.ptr,
+        FFIType.ptr,
+        FFIType.u64,
+        FFIType.ptr,
+        FFIType.u64,
+        FFIType.ptr,
+        FFIType.u64,
+      ],
+      returns: FFIType.i32,
+    },
+    apply_ops: {
+      args: [
+        FFIType.ptr,
+        FFIType.ptr,
+        FFIType.u64,
+        FFIType.ptr,
+        FFIType.u64,
+        FFIType.ptr,
+        FFIType.u64,
+      ],
+      returns: FFIType.i32,
+    },
+    compute_layout_for_root: {
+      args: [FFIType.ptr, FFIType.u32, FFIType.f32, FFIType.f32],
+      returns: FFIType.i32,
+    },
+    compute_current: {
+      args: [FFIType.ptr, FFIType.u32, FFIType.f32, FFIType.f32],
+      returns: FFIType.i32,
+    },
+    recompute_with_available: {
+      args: [FFIType.ptr, FFIType.f32, FFIType.f32],
+      returns: FFIType.i32,
+    },
+    compute_layout_for_root_with_modes: {
+      args: [FFIType.ptr, FFIType.u32, FFIType.u32, FFIType.f32, FFIType.u32, FFIType.f32],
+      returns: FFIType.i32,
+    },
+    measure_intrinsic: {
+      args: [FFIType.ptr, FFIType.u32, FFIType.u32, FFIType.u32, FFIType.ptr],
+      returns: FFIType.i32,
+    },
+    measure_node: {
+      args: [FFIType.ptr, FFIType.u32, FFIType.f32, FFIType.f32, FFIType.ptr],
+      returns: FFIType.i32,
+    },
+    dump_tree: {
+      args: [FFIType.ptr, FFIType.u32, FFIType.ptr, FFIType.u64],
+      returns: FFIType.u64,
+    },
+    export_snapshot: {
+      args: [FFIType.ptr, FFIType.ptr, FFIType.u64],
+      returns: FFIType.u64,
+    },
+    import_snapshot: {
+      args: [FFIType.ptr, FFIType.ptr, FFIType.u64],
+      returns: FFIType.i32,
+    },
+    get_changed_node_ids: {
+      args: [FFIType.ptr, FFIType.ptr, FFIType.u64],
+      returns: FFIType.u64,
+    },
+    set_skip_hidden_results: { args: [FFIType.ptr, FFIType.u32], returns: FFIType.void },
+    set_zero_size_flow_positions: { args: [FFIType.ptr, FFIType.u32], returns: FFIType.void },
+    set_timings_enabled: { args: [FFIType.ptr, FFIType.u32], returns: FFIType.void },
+    get_last_timings: { args: [FFIType.ptr, FFIType.ptr], returns: FFIType.i32 },
+    get_cache_stats: { args: [FFIType.ptr, FFIType.ptr], returns: FFIType.i32 },
+    get_invalid_style: { args: [FFIType.ptr, FFIType.ptr], returns: FFIType.i32 },
+    get_node_layout_unrounded: {
+      args: [FFIType.ptr, FFIType.u32, FFIType.ptr],
+      returns: FFIType.i32,
+    },
+    get_node_baseline: { args: [FFIType.ptr, FFIType.u32, FFIType.ptr], returns: FFIType.i32 },
+    get_node_visibility: { args: [FFIType.ptr, FFIType.u32], returns: FFIType.i32 },
+    get_absolute_layout: {
+      args: [FFIType.ptr, FFIType.u32, FFIType.ptr],
+      returns: FFIType.i32,
+    },
+    hit_test: {
+      args: [FFIType.ptr, FFIType.u32, FFIType.f32, FFIType.f32, FFIType.ptr],
+      returns: FFIType.i32,
+    },
+    get_bounding_box: {
+      args: [FFIType.ptr, FFIType.ptr, FFIType.u64, FFIType.ptr],
+      returns: FFIType.i32,
+    },
+    get_flex_line_count: { args: [FFIType.ptr, FFIType.u32], returns: FFIType.i32 },
+    layout_engine_has_node: { args: [FFIType.ptr, FFIType.u32], returns: FFIType.i32 },
+    get_results_ptr: { args: [FFIType.ptr], returns: FFIType.ptr },
+    get_results_len: { args: [FFIType.ptr], returns: FFIType.u64 },
+    get_results_capacity: { args: [FFIType.ptr], returns: FFIType.u64 },
+    get_results_chunk: {
+      args: [FFIType.ptr, FFIType.u64, FFIType.u64, FFIType.ptr, FFIType.u64],
+      returns: FFIType.u64,
+    },
+    layout_engine_error_string: { args: [FFIType.i32], returns: FFIType.cstring },
+  });
+}
+
+export type EngineLib = ReturnType<typeof openEngineLib>;
+
+export interface TestNode {
+  style?: Partial<Record<keyof typeof StyleProp, number>>;
+  children?: number[];
+}
+
+/** Encodes nodes (indexed by js id) into the flat `compute_layout_from_buffers` buffers. */
+export function encodeNodes(nodes: TestNode[]): { nodes: Float32Array; children: Uint32Array } {
+  const out = new Float32Array(nodes.length * STYLE_STRIDE);
+  const children: number[] = [];
+  nodes.forEach((node, i) => {
+    const style = out.subarray(i * STYLE_STRIDE, (i + 1) * STYLE_STRIDE);
+    encodeStyle(style, node.style);
+    const kids = node.children ?? [];
+    style[StyleProp.ChildrenOffset] = children.length;
+    style[StyleProp.ChildrenCount] = kids.length;
+    children.push(...kids);
+  });
+  return { nodes: out, children: new Uint32Array(children) };
+}
+
+export function encodeStyle(out: Float32Array, style: TestNode["style"] = {}) {
+  out.fill(0);
+  out[StyleProp.FlexShrink] = 1;
+  out[StyleProp.FlexBasis] = NaN;
+  out[StyleProp.AlignItems] = 4;
+  out[StyleProp.Width] = NaN;
+  out[StyleProp.Height] = NaN;
+  out[StyleProp.MinWidth] = NaN;
+  out[StyleProp.MinHeight] = NaN;
+  out[StyleProp.MaxWidth] = NaN;
+  out[StyleProp.MaxHeight] = NaN;
+  for (const [name, value] of Object.entries(style)) {
+    out[StyleProp[name as keyof typeof StyleProp]] = value;
+  }
+}
+
+export function computeFromNodes(lib: EngineLib, engine: Pointer, nodes: TestNode[]): number {
+  const encoded = encodeNodes(nodes);
+  return lib.symbols.compute_layout_from_buffers(
+    engine,
+    encoded.nodes.length > 0 ? ptr(encoded.nodes) : null,
+    encoded.nodes.length,
+    encoded.children.length > 0 ? ptr(encoded.children) : null,
+    encoded.children.length,
+  );
+}
+
+export enum LayoutOp {
+  CreateLeaf = 1,
+  UpdateStyle = 2,
+  SetChildren = 3,
+  RemoveNode = 4,
+  RemoveSubtree = 5,
+  SetStylePartial = 6,
+}
+
+/** Per-axis mode codes for `compute_layout_for_root_with_modes` and `measure_intrinsic`. */
+export enum AvailableSpaceMode {
+  MaxContent = 0,
+  MinContent = 1,
+  Definite = 2,
+}
+
+/** Encodes one style per entry into a flat styles payload for `apply_ops_and_compute`. */
+export function encodeStyles(styles: TestNode["style"][]): Float32Array {
+  const out = new Float32Array(styles.length * STYLE_STRIDE);
+  styles.forEach((style, i) => {
+    encodeStyle(out.subarray(i * STYLE_STRIDE, (i + 1) * STYLE_STRIDE), style);
+  });
+  return out;
+}
+
+/** Applies a batch with `apply_ops_and_compute`, or with `apply_ops` when `compute` is false. */
+export function applyOps(
+  lib: EngineLib,
+  engine: Pointer,
+  ops: number[],
+  styles: Float32Array = new Float32Array(0),
+  children: number[] = [],
+  compute = true,
+): number {
+  const opsBuf = new Uint32Array(ops);
+  const childrenBuf = new Uint32Array(children);
+  const apply = compute ? lib.symbols.apply_ops_and_compute : lib.symbols.apply_ops;
+  return apply(
+    engine,
+    opsBuf.length > 0 ? ptr(opsBuf) : null,
+    opsBuf.length,
+    styles.length > 0 ? ptr(styles) : null,
+    styles.length,
+    childrenBuf.length > 0 ? ptr(childrenBuf) : null,
+    childrenBuf.length,
+  );
+}
+
+export interface ResultRect {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+}
+
+export function readRawResults(lib: EngineLib, engine: Pointer): Float32Array {
+  const len = Number(lib.symbols.get_results_len(engine));
+  const resultsPtr = lib.symbols.get_results_ptr(engine);
+  if (!resultsPtr || len === 0) return new Float32Array(0);
+  const buffer = toArrayBuffer(resultsPtr, 0, len * Float32Array.BYTES_PER_ELEMENT);
+  return new Float32Array(buffer).slice();
+}
+
+export function readResults(lib: EngineLib, engine: Pointer): Map<number, ResultRect> {
+  const raw = readRawResults(lib, engine);
+  const results = new Map<number, ResultRect>();
+  for (let i = 0; i < raw.length; i += RESULT_STRIDE) {
+    results.set(raw[i]!, {
+      x: raw[i + 1]!,
+      y: raw[i + 2]!,
+      width: raw[i + 3]!,
+      height: raw[i + 4]!,
+    });
+  }
+  return results;
+}
@@ -1,6 +1,6 @@
 import { describe, it, expect } from "bun:test";
 import { computeLayout } from "@/layout-engine";
-import type { LayoutInputNode } from "@/types";
+import type { LayoutInputNode, LayoutStyle } from "@/types";
 
 describe("Layout Engine", () => {
   it("should compute a simple layout", () => {
@@ -144,4 +144,355 @@ describe("Layout Engine", () => {
     expect(layout2.a?.height).toBe(2);
     expect(layout2.b).toBeUndefined();
   });
+
+  it("should let overflow-hidden children shrink below their content size", () => {
+    const makeRoot = (overflow: LayoutInputNode["overflow"]): LayoutInputNode => ({
+      identifier: "root",
+      type: "block",
+      width: 100,
+      height: 20,
+      children: [
+        {
+          identifier: "panel",
+          type: "block",
+          overflow,
+          children: [{ identifier: "content", type: "block", width: 150, height: 10 }],
+        },
+      ],
+    });
+
+    expect(computeLayout(makeRoot("visible")).panel?.width).toBe(150);
+    expect(computeLayout(makeRoot("hidden")).panel?.width).toBe(100);
+    expect(computeLayout(makeRoot("scroll")).panel?.width).toBe(100);
+  });
+
+  it("should reserve a scrollbar gutter in scroll containers", () => {
+    const makeRoot = (overflowY: LayoutInputNode["overflowY"]): LayoutInputNode => ({
+      identifier: "root",
+      type: "block",
+      width: 100,
+      height: 50,
+      flexDirection: "column",
+      overflowY,
+      scrollbarWidth: 15,
+      children: [{ identifier: "content", type: "block", height: 200, flexShrink: 0 }],
+    });
+
+    expect(computeLayout(makeRoot("visible")).content?.width).toBe(100);
+    expect(computeLayout(makeRoot("scroll")).content?.width).toBe(85);
+  });
+
+  it("should resolve explicit sizes according to box-sizing", () => {
+    const makeRoot = (boxSizing: LayoutInputNode["boxSizing"]): LayoutInputNode => ({
+      identifier: "root",
+      type: "block",
+      width: 200,
+      height: 200,
+      alignItems: "flex-start",
+      children: [
+        { identifier: "box", type: "block", width: 100, height: 50, padding: 10, boxSizing },
+      ],
+    });
+
+    const borderBox = computeLayout(makeRoot("border-box")).box;
+    expect(borderBox?.width).toBe(100);
+    expect(borderBox?.height).toBe(50);
+
+    const contentBox = computeLayout(makeRoot("content-box")).box;
+    expect(contentBox?.width).toBe(120);
+    expect(contentBox?.height).toBe(70);
+  });
+
+  it("should mirror row layouts under rtl direction", () => {
+    const makeRoot = (direction: LayoutInputNode["direction"]): LayoutInputNode => ({
+      identifier: "root",
+      type: "block",
+      width: 100,
+      height: 10,
+      direction,
+      children: [
+        { identifier: "first", type: "block", width: 30, height: 10 },
+        { identifier: "second", type: "block", width: 20, height: 10 },
+      ],
+    });
+
+    const ltr = computeLayout(makeRoot("ltr"));
+    expect(ltr.first?.x).toBe(0);
+    expect(ltr.second?.x).toBe(30);
+
+    const rtl = computeLayout(makeRoot("rtl"));
+    expect(rtl.first?.x).toBe(70);
+    expect(rtl.second?.x).toBe(50);
+  });
+
+  it("should stack display block children vertically with collapsed margins", () => {
+    const layout = computeLayout({
+      identifier: "root",
+      type: "block",
+      display: "block",
+      width: 100,
+      children: [
+        { identifier: "first", type: "block", display: "block", height: 10, margin: [0, 0, 0, 10] },
+        {
+          identifier: "second",
+          type: "block",
+          display: "block",
+          width: 40,
+          height: 10,
+          margin: [0, 0, 5, 0],
+        },
+      ],
+    });
+
+    expect(layout.first).toEqual({ x: 0, y: 0, width: 100, height: 10 });
+    // Adjoining vertical margins collapse to the larger one instead of adding up as in flex.
+    expect(layout.second).toEqual({ x: 0, y: 20, width: 40, height: 10 });
+    expect(layout.root?.height).toBe(30);
+  });
+
+  it("should distribute wrapped lines with alignContent", () => {
+    const row = (identifier: string) => ({ identifier, type: "block", width: 20, height: 10 });
+    const layout = computeLayout({
+      identifier: "root",
+      type: "block",
+      width: 30,
+      height: 50,
+      flexWrap: "wrap",
+      alignContent: "space-between",
+      children: [row("a"), row("b"), row("c")],
+    });
+
+    // Each child wraps onto its own 10-cell line; the 20 spare cells go between the lines.
+    expect(layout.a).toEqual({ x: 0, y: 0, width: 20, height: 10 });
+    expect(layout.b).toEqual({ x: 0, y: 20, width: 20, height: 10 });
+    expect(layout.c).toEqual({ x: 0, y: 40, width: 20, height: 10 });
+  });
+
+  it("should justify a grid item within its cell", () => {
+    const cell = (style: LayoutStyle, itemStyle: LayoutStyle = {}) =>
+      computeLayout({
+        identifier: "grid",
+        type: "block",
+        display: "grid",
+        width: 30,
+        height: 10,
+        ...style,
+        children: [{ identifier: "item", type: "block", width: 6, height: 2, ...itemStyle }],
+      }).item;
+
+    expect(cell({}, { justifySelf: "start" })?.x).toBe(0);
+    expect(cell({}, { justifySelf: "center" })?.x).toBe(12);
+    expect(cell({}, { justifySelf: "end" })?.x).toBe(24);
+    expect(cell({ justifyItems: "center" })?.x).toBe(12);
+    expect(cell({ justifyItems: "center" }, { justifySelf: "end" })?.x).toBe(24);
+    // Flex containers ignore inline-axis item justification.
+    expect(cell({ display: "flex" }, { justifySelf: "end" })?.x).toBe(0);
+  });
+
+  it("should pull a node over its predecessor with a negative margin", () => {
+    const layout = computeLayout({
+      identifier: "root",
+      type: "block",
+      width: 50,
+      flexDirection: "column",
+      children: [
+        { identifier: "first", type: "block", height: 30 },
+        { identifier: "second", type: "block", height: 10, margin: [-5, 0, -20, 0] },
+      ],
+    });
+
+    expect(layout.first).toEqual({ x: 0, y: 0, width: 50, height: 30 });
+    // Overlaps the bottom 20 cells of "first" and sticks out 5 cells to the left.
+    expect(layout.second).toEqual({ x: -5, y: 10, width: 55, height: 10 });
+    expect(layout.root?.height).toBe(20);
+  });
+
+  it("should center a fixed-width node between auto margins", () => {
+    const layout = computeLayout({
+      identifier: "root",
+      type: "block",
+      width: 100,
+      height: 20,
+      children: [
+        { identifier: "box", type: "block", width: 40, height: 10, margin: ["auto", "auto", 0, 0] },
+      ],
+    });
+
+    expect(layout.box).toEqual({ x: 30, y: 0, width: 40, height: 10 });
+  });
+
+  it("should resolve a percentage flexBasis against the container", () => {
+    const layout = computeLayout({
+      identifier: "root",
+      type: "block",
+      width: 80,
+      height: 10,
+      children: [
+        { identifier: "half", type: "block", flexBasis: "50%", height: 1 },
+        { identifier: "auto", type: "block", flexBasis: "auto", width: 5, height: 1 },
+      ],
+    });
+
+    expect(layout.half).toEqual({ x: 0, y: 0, width: 40, height: 1 });
+    expect(layout.auto).toEqual({ x: 40, y: 0, width: 5, height: 1 });
+  });
+
+  it("should clamp flex items by their min and max sizes", () => {
+    const layout = computeLayout({
+      identifier: "root",
+      type: "block",
+      width: 100,
+      height: 10,
+      children: [
+        { identifier: "capped", type: "block", flexGrow: 1, maxWidth: 30, height: 1 },
+        { identifier: "floored", type: "block", width: 5, minWidth: 20, maxHeight: 2 },
+      ],
+    });
+
+    expect(layout.capped).toEqual({ x: 0, y: 0, width: 30, height: 1 });
+    expect(layout.floored).toEqual({ x: 30, y: 0, width: 20, height: 2 });
+  });
+
+  it("should size flex items from flexBasis before their width", () => {
+    const layout = computeLayout({
+      identifier: "root",
+      type: "block",
+      width: 100,
+      height: 10,
+      children: [
+        { identifier: "based", type: "block", flexBasis: 30, width: 10, height: 1 },
+        { identifier: "rest", type: "block", flexGrow: 1, flexBasis: 0, height: 1 },
+      ],
+    });
+
+    expect(layout.based).toEqual({ x: 0, y: 0, width: 30, height: 1 });
+    expect(layout.rest).toEqual({ x: 30, y: 0, width: 70, height: 1 });
+  });
+
+  it("should lay children out by order instead of tree order", () => {
+    const tree = (order: number): LayoutInputNode => ({
+      identifier: "root",
+      type: "block",
+      children: [
+        { identifier: "a", type: "block", width: 10, height: 1, order },
+        { identifier: "b", type: "block", width: 20, height: 1 },
+      ],
+    });
+
+    let layout = computeLayout(tree(1));
+    expect(layout.a?.x).toBe(20);
+    expect(layout.b?.x).toBe(0);
+
+    // Only "a"'s style changes, so this goes through the incremental path.
+    layout = computeLayout(tree(0));
+    expect(layout.a?.x).toBe(0);
+    expect(layout.b?.x).toBe(10);
+  });
+
+  it("should space items on a line by gap width and lines by gap height", () => {
+    const layout = computeLayout({
+      identifier: "root",
+      type: "block",
+      width: 25,
+      flexWrap: "wrap",
+      gap: { width: 5, height: 2 },
+      children: ["a", "b", "c"].map((identifier) => ({
+        identifier,
+        type: "block",
+        width: 10,
+        height: 3,
+      })),
+    });
+
+    expect(layout.b).toEqual({ x: 15, y: 0, width: 10, height: 3 });
+    expect(layout.c).toEqual({ x: 0, y: 5, width: 10, height: 3 });
+    expect(layout.root?.height).toBe(8);
+  });
+
+  it("should keep the column gap at 0 when only the row gap is given", () => {
+    const layout = computeLayout({
+      identifier: "root",
+      type: "block",
+      width: 25,
+      flexWrap: "wrap",
+      gap: { height: 2 },
+      children: ["a", "b", "c"].map((identifier) => ({
+        identifier,
+        type: "block",
+        width: 10,
+        height: 3,
+      })),
+    });
+
+    expect(layout.b).toEqual({ x: 10, y: 0, width: 10, height: 3 });
+    expect(layout.c).toEqual({ x: 0, y: 5, width: 10, height: 3 });
+  });
+
+  it("should resolve a percentage gap against the container width", () => {
+    const layout = computeLayout({
+      identifier: "root",
+      type: "block",
+      width: 200,
+      gap: { width: "10%" },
+      children: [
+        { identifier: "a", type: "block", width: 10 },
+        { identifier: "b", type: "block", width: 10 },
+      ],
+    });
+
+    expect(layout.a?.x).toBe(0);
+    expect(layout.b?.x).toBe(30);
+  });
+
+  it("should size a grid item's auto height from its aspect ratio", () => {
+    const layout = computeLayout({
+      identifier: "root",
+      type: "block",
+      display: "grid",
+      width: 40,
+      children: [{ identifier: "thumb", type: "block", aspectRatio: 2 }],
+    });
+
+    expect(layout.thumb).toEqual({ x: 0, y: 0, width: 40, height: 20 });
+    expect(layout.root?.height).toBe(20);
+  });
+
+  it("should place an anchorRoot overlay against the root, not its parent", () => {
+    const layout = computeLayout({
+      identifier: "root",
+      type: "block",
+      width: 100,
+      height: 100,
+      flexDirection: "column",
+      padding: [2, 0, 2, 0],
+      children: [
+        {
+          identifier: "panel",
+          type: "block",
+          width: 50,
+          height: 50,
+          margin: [10, 0, 20, 0],
+          children: [
+            { identifier: "item", type: "block", width: 5, height: 5 },
+            {
+              identifier: "overlay",
+              type: "block",
+              position: "absolute",
+              anchorRoot: true,
+              width: 30,
+              height: 5,
+              margin: [1, 0, 0, 0],
+            },
+          ],
+        },
+      ],
+    });
+
+    expect(layout.panel).toEqual({ x: 12, y: 22, width: 50, height: 50 });
+    // Still reported relative to the panel, so accumulating offsets lands in root space.
+    expect(layout.overlay).toEqual({ x: -9, y: -20, width: 30, height: 5 });
+    expect(layout.panel!.x + layout.overlay!.x).toBe(3);
+    expect(layout.panel!.y + layout.overlay!.y).toBe(2);
+    expect(layout.item).toEqual({ x: 0, y: 0, width: 5, height: 5 });
+  });
 });